@@ -0,0 +1,74 @@
+use ::gloo::utils;
+use ::rand::{Rng, rngs::OsRng};
+use ::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use ::web_sys::{AudioContext, SpeechSynthesisUtterance};
+use crate::{CheckCodeError, Language};
+// 底噪的音量与时长。底噪只是为了干扰机器识别，而不应盖过人声。
+const NOISE_GAIN: f32 = 0.08;
+const SECONDS_PER_CHAR: f64 = 1.2;
+/// 语音验证码的单次播放。它与图形验证码共用同一个答案。
+pub(crate) struct AudioPlayback {
+    context: AudioContext
+}
+impl AudioPlayback {
+    // 经由`speechSynthesis`逐字朗读验证码，同时经由`AudioContext`播放一段白噪声底噪
+    pub(crate) fn play(check_code: &str, lang: Language) -> Result<Self, CheckCodeError> {
+        let text = spell(check_code, lang).ok_or(CheckCodeError::AudioUnavailable)?;
+        Self::try_play(check_code, &text[..], lang).map_err(|_| CheckCodeError::AudioUnavailable)
+    }
+    fn try_play(check_code: &str, text: &str, lang: Language) -> Result<Self, JsValue> {
+        let speech_synthesis = utils::window().speech_synthesis()?;
+        let context = AudioContext::new()?;
+        let sample_rate = context.sample_rate();
+        let length = ((check_code.chars().count() as f64 + 1_f64) * SECONDS_PER_CHAR * sample_rate as f64) as u32;
+        let buffer = context.create_buffer(1, length, sample_rate)?;
+        let samples = (0..length).map(|_| OsRng.gen_range(-1_f32..1_f32)).collect::<Vec<_>>();
+        buffer.copy_to_channel(&samples[..], 0)?;
+        let source = context.create_buffer_source()?;
+        source.set_buffer(Some(&buffer));
+        let gain = context.create_gain()?;
+        gain.gain().set_value(NOISE_GAIN);
+        source.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        let utterance = SpeechSynthesisUtterance::new_with_text(text)?;
+        utterance.set_lang(match lang {
+            Language::Zh => "zh-CN",
+            Language::En => "en-US"
+        });
+        utterance.set_rate(0.7);
+        // 读完即关闭底噪，而不必等到换一张或卸载控件
+        let noise = context.clone();
+        let onend = Closure::once_into_js(move || {
+            let _ = noise.close();
+        });
+        utterance.set_onend(Some(onend.unchecked_ref()));
+        // 打断上一次尚未读完的朗读
+        speech_synthesis.cancel();
+        source.start()?;
+        speech_synthesis.speak(&utterance);
+        Ok(Self {context})
+    }
+    pub(crate) fn stop(self) {
+        if let Ok(speech_synthesis) = utils::window().speech_synthesis() {
+            speech_synthesis.cancel();
+        }
+        let _ = self.context.close();
+    }
+}
+// 能被`lang`语音读得清的字符。同音字（如“他她它”）无法经由读音区分，而英文语音也读不出汉字。
+pub(crate) fn speakable(char: char, lang: Language) -> bool {
+    char.is_ascii_alphanumeric() || lang == Language::Zh && matches!(char, '你' | '我')
+}
+// 逐字拼读。英文字母的大小写在读音上无从区分，所以须被念出来。验证码含有读不清的字符时，返回`None`。
+fn spell(check_code: &str, lang: Language) -> Option<String> {
+    let (upper, lower, separator) = match lang {
+        Language::Zh => ("大写", "小写", "，"),
+        Language::En => ("capital", "lowercase", ", ")
+    };
+    check_code.chars().map(|char| match char {
+        'A'..='Z' => Some(format!("{upper} {char}")),
+        'a'..='z' => Some(format!("{lower} {char}")),
+        _ if speakable(char, lang) => Some(char.to_string()),
+        _ => None
+    }).collect::<Option<Vec<_>>>().map(|chars| chars.join(separator))
+}
@@ -0,0 +1,138 @@
+use crate::{InteractionTrace, Point};
+/// 人机验证的题型
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ChallengeMode {
+    /// 敲入图片里的字符
+    #[default]
+    Text,
+    /// 依次点击图片里被打散的字符
+    ClickOrder,
+    /// 拖动滑块，使拼图块与缺口对齐
+    Slider,
+    /// 拖动滑块，把被随机旋转的字符转正
+    Rotate
+}
+impl ChallengeMode {
+    // 须经由滑轨作答的题型
+    pub(crate) fn has_slider(&self) -> bool {
+        matches!(self, ChallengeMode::Slider | ChallengeMode::Rotate)
+    }
+}
+/// `UI`用户对非文字题型的作答
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChallengeAnswer {
+    /// 依次点击的位置
+    ClickOrder(Vec<Point>),
+    /// 拼图块最终的平移距离，及其拖动轨迹
+    Slider {
+        offset: f64,
+        trajectory: Vec<TrajectoryPoint>
+    },
+    /// 字符最终被顺时针旋转的角度（`0..=360`度），及滑块的拖动轨迹
+    Rotate {
+        angle: f64,
+        trajectory: Vec<TrajectoryPoint>
+    }
+}
+/// 拖动轨迹上的一点
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryPoint {
+    /// 滑块当时的平移距离
+    pub offset: f64,
+    /// 指针相对于拖动起点的纵向偏移
+    pub dy: f64,
+    /// 自拖动开始以来的毫秒数
+    pub time: f64
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChallengeResponse {
+    /// 作答时的验证码随机编号（见`Props::hidden_input`）
+    pub challenge_id: String,
+    pub answer: ChallengeAnswer,
+    /// 开启了`Props::trace`时，`UI`用户作答过程的交互痕迹
+    pub trace: Option<InteractionTrace>
+}
+/// 逐一比对点选位置与字符中心点（皆依验证码的字符顺序排列）。每次点选与对应字符中心点的距离都不大于`tolerance`才算通过。
+pub fn verify_click_order(targets: &[Point], clicks: &[Point], tolerance: f64) -> bool {
+    targets.len() == clicks.len() && targets.iter().zip(clicks).all(|(target, click)| {
+        (target.0 - click.0).hypot(target.1 - click.1) <= tolerance
+    })
+}
+/// 拼图块的平移距离与缺口位置之差不大于`tolerance`才算通过。拖动轨迹是否像人手所为，留给后端判断。
+pub fn verify_slider(target: f64, offset: f64, tolerance: f64) -> bool {
+    (target - offset).abs() <= tolerance
+}
+/// 旋转角度与转正所需角度在圆周上的夹角不大于`tolerance`（单位是度）才算通过
+pub fn verify_rotation(target: f64, angle: f64, tolerance: f64) -> bool {
+    let difference = (target - angle).rem_euclid(360_f64);
+    difference.min(360_f64 - difference) <= tolerance
+}
+// 滑块拼图与转正题型的拖动状态
+#[derive(Debug, Default)]
+pub(crate) struct Slide {
+    max: f64,
+    pub(crate) offset: f64,
+    // 首次拖动（或按键）的时间戳
+    started_at: Option<f64>,
+    // 正被拖动时，拖动起点的指针位置与平移距离
+    origin: Option<(Point, f64)>,
+    trajectory: Vec<TrajectoryPoint>,
+    done: bool
+}
+impl Slide {
+    pub(crate) fn new(max: f64) -> Self {
+        Slide {max: max.max(0_f64), ..Default::default()}
+    }
+    pub(crate) fn max(&self) -> f64 {
+        self.max
+    }
+    // 转正题型：滑轨的全长对应一整圈
+    pub(crate) fn angle(&self) -> f64 {
+        if self.max > 0_f64 {
+            self.offset / self.max * 360_f64
+        } else {
+            0_f64
+        }
+    }
+    pub(crate) fn start(&mut self, pointer: Point, time: f64) -> bool {
+        if self.done {
+            return false;
+        }
+        self.origin = Some((pointer, self.offset));
+        self.record(0_f64, time);
+        true
+    }
+    pub(crate) fn move_to(&mut self, pointer: Point, time: f64) -> bool {
+        let Some((origin, origin_offset)) = self.origin else {
+            return false;
+        };
+        self.offset = (origin_offset + pointer.0 - origin.0).clamp(0_f64, self.max);
+        self.record(pointer.1 - origin.1, time);
+        true
+    }
+    // 键盘操作：每次按键平移`delta`
+    pub(crate) fn step(&mut self, delta: f64, time: f64) -> bool {
+        if self.done {
+            return false;
+        }
+        self.offset = (self.offset + delta).clamp(0_f64, self.max);
+        self.record(0_f64, time);
+        true
+    }
+    // 松开指针（或按下回车键）即作答完毕。此后的拖动被忽略，直至换一张。
+    pub(crate) fn finish(&mut self) -> Option<ChallengeAnswer> {
+        if self.done || self.started_at.is_none() {
+            return None;
+        }
+        self.origin = None;
+        self.done = true;
+        Some(ChallengeAnswer::Slider {
+            offset: self.offset,
+            trajectory: std::mem::take(&mut self.trajectory)
+        })
+    }
+    fn record(&mut self, dy: f64, time: f64) {
+        let started_at = *self.started_at.get_or_insert(time);
+        self.trajectory.push(TrajectoryPoint {offset: self.offset, dy, time: time - started_at});
+    }
+}
@@ -0,0 +1,143 @@
+use ::js_sys::{Function, Promise};
+use ::std::{cell::RefCell, fmt, rc::Rc};
+use ::wasm_bindgen::{JsCast, JsValue};
+use ::wasm_bindgen_futures::JsFuture;
+use ::web_sys::Blob;
+use ::yew::{Callback, html::Scope};
+use crate::{CanvasCheckCode, CheckCode, CheckCodeError, Difficulty, InteractionTrace, Message, Point, verify_click_order, verify_rotation, verify_slider};
+/// 从控件外部操作`<CanvasCheckCode>`的句柄。经由`Props::controller`交给控件之后，父控件就能程序地刷新、
+/// 重置验证码与调整干扰强度，而不必接触控件的`Scope`。其复本共享同一份状态，所以可被随意地克隆。
+#[derive(Clone, Default)]
+pub struct CheckCodeController {
+    inner: Rc<ControllerInner>
+}
+#[derive(Default)]
+struct ControllerInner {
+    scope: RefCell<Option<Scope<CanvasCheckCode>>>,
+    check_code: RefCell<Option<String>>,
+    challenge_id: RefCell<Option<String>>,
+    click_targets: RefCell<Vec<Point>>,
+    slider_target: RefCell<Option<f64>>,
+    rotation_target: RefCell<Option<f64>>,
+    // 尚未落定的`to_blob()`的`reject`函数。控件被卸载时，它们都被拒绝，以免`to_blob()`永不返回。
+    pending_blobs: RefCell<Vec<Function>>
+}
+impl CheckCodeController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 生成新验证码
+    pub fn refresh(&self) {
+        self.send_message(Message::UpdateCheckCode);
+    }
+    /// 撤销`set_difficulty(..)`的设置，清除错误提示，并生成新验证码
+    pub fn reset(&self) {
+        self.send_message(Message::Reset);
+    }
+    /// 按新的干扰强度生成新验证码
+    pub fn set_difficulty(&self, difficulty: Difficulty) {
+        self.send_message(Message::SetDifficulty(difficulty));
+    }
+    /// 以`data:image/png;base64,..`格式导出当前验证码图片
+    pub fn export_image(&self, callback: impl Into<Callback<String>>) {
+        self.to_data_url("image/png", None, callback);
+    }
+    /// 以`data:`网址导出当前验证码图片。`mime`是图片格式（比如，`image/jpeg`或`image/webp`），而`quality`是
+    /// `0`至`1`之间的压缩质量（仅对有损格式有效）。浏览器不支持的格式会被替换为`image/png`。
+    pub fn to_data_url(&self, mime: &str, quality: Option<f64>, callback: impl Into<Callback<String>>) {
+        self.send_message(Message::ExportImage(mime.to_string(), quality, callback.into()));
+    }
+    /// 以`Blob`异步地导出当前验证码图片，以便上传或经由`URL.createObjectURL()`展示。控件尚未被挂载时，立即返回错误；
+    /// 导出完成之前控件就被卸载（或换用了别的句柄）时，也返回错误。
+    pub async fn to_blob(&self, mime: &str, quality: Option<f64>) -> Result<Blob, CheckCodeError> {
+        if self.inner.scope.borrow().is_none() {
+            return Err(CheckCodeError::MissingCanvas);
+        }
+        let mut pending = None;
+        let promise = Promise::new(&mut |resolve, reject: Function| {
+            self.inner.pending_blobs.borrow_mut().push(reject.clone());
+            self.send_message(Message::ExportBlob(mime.to_string(), quality, resolve, reject.clone()));
+            pending = Some(reject);
+        });
+        let blob = JsFuture::from(promise).await;
+        if let Some(reject) = pending {
+            self.inner.pending_blobs.borrow_mut().retain(|pending| pending != &reject);
+        }
+        blob.map_err(|_| CheckCodeError::ExportFailure)?.dyn_into::<Blob>().map_err(|_| CheckCodeError::ExportFailure)
+    }
+    /// 截至当下的交互痕迹。没有开启`Props::trace`时，回传`None`。
+    pub fn interaction_trace(&self, callback: impl Into<Callback<Option<InteractionTrace>>>) {
+        self.send_message(Message::ExportTrace(callback.into()));
+    }
+    pub fn verify(&self, input: &str) -> bool {
+        self.inner.check_code.borrow().as_deref() == Some(input)
+    }
+    pub fn current_challenge(&self) -> Option<String> {
+        self.inner.check_code.borrow().clone()
+    }
+    /// 当前验证码的随机编号。每次生成新验证码都会换新，而重绘则不会。它与`hidden_input`隐藏字段的值相同。
+    pub fn challenge_id(&self) -> Option<String> {
+        self.inner.challenge_id.borrow().clone()
+    }
+    /// 点选模式下，各个字符的中心点（依验证码的字符顺序排列）。每次重绘都会变化。
+    pub fn click_targets(&self) -> Vec<Point> {
+        self.inner.click_targets.borrow().clone()
+    }
+    pub fn verify_clicks(&self, clicks: &[Point], tolerance: f64) -> bool {
+        verify_click_order(&self.inner.click_targets.borrow()[..], clicks, tolerance)
+    }
+    /// 滑块拼图题型下，拼图块与缺口对齐所需的平移距离。每次重绘都会变化。
+    pub fn slider_target(&self) -> Option<f64> {
+        *self.inner.slider_target.borrow()
+    }
+    pub fn verify_slide(&self, offset: f64, tolerance: f64) -> bool {
+        self.slider_target().is_some_and(|target| verify_slider(target, offset, tolerance))
+    }
+    /// 转正题型下，把字符转正所需的顺时针旋转角度。每次重绘都会变化。
+    pub fn rotation_target(&self) -> Option<f64> {
+        *self.inner.rotation_target.borrow()
+    }
+    pub fn verify_rotation(&self, angle: f64, tolerance: f64) -> bool {
+        self.rotation_target().is_some_and(|target| verify_rotation(target, angle, tolerance))
+    }
+    // 控件尚未被挂载时，命令被丢弃
+    fn send_message(&self, message: Message) {
+        if let Some(scope) = self.inner.scope.borrow().as_ref() {
+            scope.send_message(message);
+        }
+    }
+    pub(crate) fn attach(&self, scope: Scope<CanvasCheckCode>) {
+        self.inner.scope.replace(Some(scope));
+    }
+    pub(crate) fn detach(&self) {
+        self.inner.scope.replace(None);
+        self.inner.check_code.replace(None);
+        self.inner.challenge_id.replace(None);
+        self.inner.click_targets.replace(Vec::new());
+        self.inner.slider_target.replace(None);
+        self.inner.rotation_target.replace(None);
+        for reject in self.inner.pending_blobs.take() {
+            let _ = reject.call1(&JsValue::NULL, &JsValue::from(CheckCodeError::MissingCanvas.to_string()));
+        }
+    }
+    pub(crate) fn on_check_code(&self, check_code: &CheckCode, challenge_id: &str) {
+        let (CheckCode::Initialize(value) | CheckCode::Update(value) | CheckCode::Redraw(value)) = check_code;
+        self.inner.check_code.replace(Some(value.clone()));
+        self.inner.challenge_id.replace(Some(challenge_id.to_string()));
+    }
+    pub(crate) fn on_redraw(&self, glyph_centers: &[Point], slider_target: Option<f64>, rotation_target: Option<f64>) {
+        self.inner.click_targets.replace(glyph_centers.to_vec());
+        self.inner.slider_target.replace(slider_target);
+        self.inner.rotation_target.replace(rotation_target);
+    }
+}
+impl PartialEq for CheckCodeController {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+impl fmt::Debug for CheckCodeController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckCodeController").field("attached", &self.inner.scope.borrow().is_some()).finish()
+    }
+}
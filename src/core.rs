@@ -3,21 +3,91 @@
 #[cfg(debug_assertions)]
 use ::gloo::console;
 mod canvas_options;
+mod context2d;
+mod css_length;
+mod noise_shape;
+mod poisson_disk;
+mod puzzle;
 use ::rand::{Rng, rngs::OsRng};
-use ::wasm_bindgen::JsValue;
-use ::web_sys::CanvasRenderingContext2d;
-pub use canvas_options::CanvasOpts;
-struct Point(f64, f64);
-pub fn redraw(context: CanvasRenderingContext2d, canvas_opts: CanvasOpts, text: &str) -> Result<(), JsValue> {
+use ::std::f64::consts::{PI, SQRT_2};
+pub use canvas_options::{CanvasOpts, CanvasOptsBuilder, Difficulty, OptionsError, Sizing};
+pub use context2d::{Context2d, RenderTarget};
+pub use css_length::CssLength;
+pub use noise_shape::{NoiseShape, NoiseShapeKind};
+use crate::{ChallengeMode, CheckCodeError};
+use poisson_disk::PoissonDisk;
+/// 画布上的一点，单位是`CSS`像素
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point(pub f64, pub f64);
+// 单个验证码字符的排版结果。包围盒是以`center`为中心、边长为`half_extent * 2`的正方形。
+struct GlyphBox {
+    char: char,
+    center: Point,
+    half_extent: f64,
+    degree: f64,
+    color: &'static str,
+    font: String
+}
+impl GlyphBox {
+    fn overlaps(&self, point: &Point, half_extent: f64) -> bool {
+        (self.center.0 - point.0).abs() < self.half_extent + half_extent &&
+        (self.center.1 - point.1).abs() < self.half_extent + half_extent
+    }
+}
+// 一次重绘实际摆放的背景干扰图案与诱饵字符个数。画布太小时，它们可能少于配置值。
+// `glyph_centers`是各个验证码字符的中心点，依验证码的字符顺序排列。
+// `slider_target`是滑块拼图题型下，拼图块与缺口对齐所需的平移距离。
+// `rotation_target`是转正题型下，把字符转正所需的顺时针旋转角度。
+#[derive(Debug)]
+pub struct RedrawStats {
+    pub shape_count: usize,
+    pub decoy_count: usize,
+    pub glyph_centers: Vec<Point>,
+    pub slider_target: Option<f64>,
+    pub rotation_target: Option<f64>
+}
+/// 在控件之外，把`text`的图形验证码绘制到`target`画布上，以便非`Yew`代码与`Web Worker`复用同一个渲染器。`canvas_opts`
+/// 通常由`CanvasOpts::builder()`构建，其`width`与`height`是图片的`CSS`尺寸；画布的像素缓冲区则被设为其`pixel_ratio`倍。
+/// 滑块拼图与转正题型还需一块叠放的拼图块画布，所以在此按文字题型绘制。
+pub fn render(target: &RenderTarget, canvas_opts: CanvasOpts, pixel_ratio: f64, text: &str) -> Result<RedrawStats, CheckCodeError> {
+    target.set_size((canvas_opts.width * pixel_ratio).round() as u32, (canvas_opts.height * pixel_ratio).round() as u32);
+    let context = target.context()?;
+    context.set_transform(pixel_ratio, 0_f64, 0_f64, pixel_ratio, 0_f64, 0_f64)?;
+    redraw(&*context, None, canvas_opts, text)
+}
+// 滑块拼图与转正题型须提供拼图块画布的`piece_context`
+pub fn redraw(context: &dyn Context2d, piece_context: Option<&dyn Context2d>, canvas_opts: CanvasOpts, text: &str) -> Result<RedrawStats, CheckCodeError> {
     context.clear_rect(0_f64, 0_f64, canvas_opts.width, canvas_opts.height);
-    let background_color = draw_background_color(&context, &canvas_opts);
+    let background_color = draw_background_color(context, &canvas_opts);
     #[cfg(debug_assertions)]
     console::info!("canvas_opts=", canvas_opts.to_string(), "background_color=", background_color, "text=", text);
-    draw_stars(&context, &canvas_opts, background_color)?;
-    draw_text(&context, &canvas_opts, text)?;
-    Ok(())
+    // 先排版验证码字符，以便形似字符的背景图案避开它们
+    let glyphs = match (canvas_opts.challenge_mode, piece_context) {
+        (ChallengeMode::Slider | ChallengeMode::Rotate, Some(_)) => vec![],
+        _ => layout_text(context, &canvas_opts, text)?
+    };
+    let shape_count = draw_noise_shapes(context, &canvas_opts, background_color, &glyphs)?;
+    match (canvas_opts.challenge_mode, piece_context) {
+        (ChallengeMode::Slider, Some(piece_context)) => {
+            let slider_target = puzzle::draw_puzzle(context, piece_context, &canvas_opts)?;
+            return Ok(RedrawStats {shape_count, decoy_count: 0, glyph_centers: vec![], slider_target: Some(slider_target), rotation_target: None});
+        },
+        (ChallengeMode::Rotate, Some(piece_context)) => {
+            let rotation_target = draw_rotated_glyph(piece_context, &canvas_opts)?;
+            return Ok(RedrawStats {shape_count, decoy_count: 0, glyph_centers: vec![], slider_target: None, rotation_target: Some(rotation_target)});
+        },
+        _ => ()
+    }
+    let decoy_count = draw_decoys(context, &canvas_opts, &glyphs)?;
+    draw_text(context, &glyphs)?;
+    let glyph_centers = glyphs.iter().map(|glyph| glyph.center).collect();
+    #[cfg(debug_assertions)]
+    if shape_count < canvas_opts.star_count as usize || decoy_count < canvas_opts.decoy_count as usize {
+        console::warn!("画布空间不足", format!("背景图案 {}/{}，诱饵字符 {}/{}", shape_count, canvas_opts.star_count, decoy_count, canvas_opts.decoy_count));
+    }
+    Ok(RedrawStats {shape_count, decoy_count, glyph_centers, slider_target: None, rotation_target: None})
 }
-fn draw_background_color(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts) -> &'static str {
+fn draw_background_color(context: &dyn Context2d, canvas_opts: &CanvasOpts) -> &'static str {
     context.save();
     let background_color = calc_color();
     context.set_fill_style(&background_color.into());
@@ -34,57 +104,52 @@ fn draw_background_color(context: &CanvasRenderingContext2d, canvas_opts: &Canva
         COLORS[index]
     }
 }
-fn draw_stars(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts, background_color: &str) -> Result<(), JsValue> {
-    let mut points: Vec<Point> = vec![];
+fn draw_noise_shapes(context: &dyn Context2d, canvas_opts: &CanvasOpts, background_color: &str, glyphs: &[GlyphBox]) -> Result<usize, CheckCodeError> {
     let mut degrees: Vec<f64> = vec![];
     let mut rgbas = vec![background_color.to_string()];
-    let space_threshold = canvas_opts.star_size * 2_f64;
-    for _ in 0..canvas_opts.star_count {
-        let point = match calc_point_rel_to(&points, space_threshold, canvas_opts) {
-            Some(point) => point,
+    let points = PoissonDisk::new(
+        canvas_opts.star_size,
+        canvas_opts.star_size,
+        canvas_opts.viewport_width(),
+        canvas_opts.viewport_height(),
+        canvas_opts.star_size * 2_f64
+    ).sample(canvas_opts.star_count as usize, |_| true);
+    let mut placed = 0_usize;
+    for point in points {
+        let shape = match pick_shape(&canvas_opts.noise_shapes) {
+            Some(shape) => shape,
             None => break
         };
+        if shape.mimics_text() && glyphs.iter().any(|glyph| glyph.overlaps(&point, decoy_half_extent(canvas_opts))) {
+            continue;
+        }
         let degree = calc_rotation_rel_to(&degrees);
         let rgba = calc_color_rel_to(&rgbas);
-        let corner_count = calc_corner_count();
-        draw_star(context, canvas_opts.star_size, &point, degree, &rgba[..], corner_count)?;
-        points.push(point);
+        shape.draw(context, canvas_opts, &point, degree, &rgba[..])?;
         degrees.push(degree);
         rgbas.push(rgba);
+        placed += 1;
     }
-    return Ok(());
-    fn calc_point_rel_to(points: &Vec<Point>, space_threshold: f64, canvas_opts: &CanvasOpts) -> Option<Point> {
-        let mut index = 0_u16;
-        return loop {
-            if index > 600_u16 {
-                break None;
-            }
-            index += 1;
-            let point = Point(
-                OsRng.gen_range(canvas_opts.star_size..canvas_opts.viewport_width() + 0.1),
-                OsRng.gen_range(canvas_opts.star_size..canvas_opts.viewport_height() + 0.1)
-            );
-            if points.len() == 0 {
-                break Some(point);
-            }
-            let min_space = match points.iter().map(calc_distance_builder(&point)).min_by(|a, b| a.total_cmp(b)) {
-                Some(min_space) => min_space,
-                None => break Some(point)
-            };
-            if min_space >= space_threshold {
-                break Some(point);
-            }
-        };
-        fn calc_distance_builder<'a>(point1: &'a Point) -> impl Fn(&'a Point) -> f64 {
-            move |point2| {
-                ((point1.0 - point2.0).powf(2_f64) + (point1.1 - point2.1).powf(2_f64)).sqrt()
-            }
+    return Ok(placed);
+    // 按权重，从形状混合列表中随机挑选一种背景图案
+    fn pick_shape(noise_shapes: &[(NoiseShapeKind, u8)]) -> Option<&dyn NoiseShape> {
+        let total = noise_shapes.iter().map(|(_, weight)| *weight as u32).sum::<u32>();
+        if total == 0 {
+            return None;
         }
+        let mut ticket = OsRng.gen_range(0..total);
+        noise_shapes.iter().find(|(_, weight)| {
+            if ticket < *weight as u32 {
+                return true;
+            }
+            ticket -= *weight as u32;
+            false
+        }).map(|(kind, _)| kind.shape())
     }
-    fn calc_rotation_rel_to(degrees: &Vec<f64>) -> f64 {
+    fn calc_rotation_rel_to(degrees: &[f64]) -> f64 {
         loop {
             let degree = OsRng.gen_range(0_f64..360_f64);
-            if degrees.len() == 0 {
+            if degrees.is_empty() {
                 break degree;
             }
             if !degrees.contains(&degree) {
@@ -92,7 +157,7 @@ fn draw_stars(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts, back
             }
         }
     }
-    fn calc_color_rel_to(colors: &Vec<String>) -> String {
+    fn calc_color_rel_to(colors: &[String]) -> String {
         let opacity = format!("{:.2}", 0.4 + OsRng.gen_range(0_f64..0.31_f64));
         return loop {
             let red = OsRng.gen_range(0_u16..256_u16).to_string();
@@ -117,60 +182,55 @@ fn draw_stars(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts, back
             }
         }
     }
-    fn calc_corner_count() -> u8 {
-        3_u8 + OsRng.gen_range(0_u8..5_u8)
-    }
-    fn draw_star(context: &CanvasRenderingContext2d, long_radius: f64, point: &Point, degree: f64, rgba: &str, corner_count: u8) -> Result<(), JsValue> {
-        let short_radius = long_radius / 2.5;
-        let c1 = 360_f64 / corner_count as f64;
-        let c2 = 90_f64 / corner_count as f64;
-        let c3 = 270_f64 / corner_count as f64;
-        context.save();
-        context.translate(point.0, point.1)?;
-        context.rotate(degree.to_radians())?;
-        context.set_fill_style(&rgba.into());
-        context.begin_path();
-        for i in 0..corner_count {
-            let i = i as f64;
-            context.line_to(
-                (c2 + i * c1).to_radians().cos() * long_radius,
-                (c2 + i * c1).to_radians().sin() * long_radius
-            );
-            context.line_to(
-                (c3 + i * c1).to_radians().cos() * short_radius,
-                (c3 + i * c1).to_radians().sin() * short_radius
-            );
-        }
-        context.close_path();
-        context.fill();
-        context.restore();
-        Ok(())
-    }
 }
-fn draw_text(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts, text: &str) -> Result<(), JsValue> {
+fn layout_text(context: &dyn Context2d, canvas_opts: &CanvasOpts, text: &str) -> Result<Vec<GlyphBox>, CheckCodeError> {
     let mut acc_width = canvas_opts.star_size;
-    let width_unit = canvas_opts.viewport_width() / text.len() as f64;
+    let width_unit = canvas_opts.viewport_width() / text.chars().count() as f64;
     let half_width_unit = width_unit / 2_f64;
     let middle_y = canvas_opts.height / 2_f64;
+    let font = format!("{} normal bolder {}px Arial icon", calc_font_style(), canvas_opts.font_size);
+    let mut glyphs = vec![];
     context.save();
-    context.set_text_align("center");
-    context.set_text_baseline("middle");
-    context.set_font(&format!("{} normal bolder {}px Arial icon", calc_font_style(), canvas_opts.font_size)[..]);
+    context.set_font(&font[..]);
     for char in text.chars() {
         let m = context.measure_text(&char.to_string()[..])?;
-        context.save();
-        context.translate(
-            acc_width + half_width_unit + OsRng.gen_range(0_f64..m.width() / 5_f64 + 0.001_f64).copysign(OsRng.gen_range(-0.1..0.1)),
-            middle_y + OsRng.gen_range(0_f64..canvas_opts.font_size / 2_f64 + 0.001_f64).copysign(OsRng.gen_range(-0.1..0.1))
-        )?;
-        context.rotate(OsRng.gen_range(0_f64..20.001_f64).to_radians().copysign(OsRng.gen_range(-0.3..0.1)))?;
-        context.set_fill_style(&calc_color().into());
-        context.fill_text(&char.to_string()[..], 0_f64, 0_f64)?;
-        context.restore();
+        glyphs.push(GlyphBox {
+            char,
+            center: Point(
+                acc_width + half_width_unit + OsRng.gen_range(0_f64..m.width() / 5_f64 + 0.001_f64).copysign(OsRng.gen_range(-0.1..0.1)),
+                middle_y + OsRng.gen_range(0_f64..canvas_opts.font_size / 2_f64 + 0.001_f64).copysign(OsRng.gen_range(-0.1..0.1))
+            ),
+            // 字符可能被旋转，所以取其外接正方形的对角线之半作为包围盒的半边长
+            half_extent: m.width().max(canvas_opts.font_size) / 2_f64 * SQRT_2,
+            degree: OsRng.gen_range(0_f64..20.001_f64).copysign(OsRng.gen_range(-0.3..0.1)),
+            color: calc_color(),
+            font: font.clone()
+        });
         acc_width += width_unit;
     }
     context.restore();
-    return Ok(());
+    if canvas_opts.challenge_mode == ChallengeMode::ClickOrder {
+        scatter_glyphs(canvas_opts, &mut glyphs);
+    }
+    return Ok(glyphs);
+    // 点选模式下，字符被打散到画布各处，而不是从左到右排列。画布太小而放不下时，保持从左到右的排列。
+    fn scatter_glyphs(canvas_opts: &CanvasOpts, glyphs: &mut [GlyphBox]) {
+        let half_extent = glyphs.iter().map(|glyph| glyph.half_extent).fold(0_f64, f64::max);
+        let points = PoissonDisk::new(
+            half_extent,
+            half_extent,
+            canvas_opts.width - half_extent,
+            canvas_opts.height - half_extent,
+            half_extent * 2_f64
+        ).sample(glyphs.len(), |_| true);
+        if points.len() < glyphs.len() {
+            return;
+        }
+        for (glyph, point) in glyphs.iter_mut().zip(points) {
+            glyph.center = point;
+            glyph.degree = OsRng.gen_range(-45_f64..45_f64);
+        }
+    }
     fn calc_font_style() -> &'static str {
         const STYLES: [&str; 3] = [
             "normal",
@@ -189,3 +249,99 @@ fn draw_text(context: &CanvasRenderingContext2d, canvas_opts: &CanvasOpts, text:
         COLORS[OsRng.gen_range(0..COLORS.len())]
     }
 }
+fn draw_text(context: &dyn Context2d, glyphs: &[GlyphBox]) -> Result<(), CheckCodeError> {
+    context.save();
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    for glyph in glyphs {
+        context.save();
+        context.set_font(&glyph.font[..]);
+        context.translate(glyph.center.0, glyph.center.1)?;
+        context.rotate(glyph.degree.to_radians())?;
+        context.set_fill_style(&glyph.color.into());
+        context.fill_text(&glyph.char.to_string()[..], 0_f64, 0_f64)?;
+        context.restore();
+    }
+    context.restore();
+    Ok(())
+}
+// 诱饵字符的字号不超过真实字符的一半，且不透明度封顶 0.3（真实字符是 1），以保证人眼能一眼区分二者。
+const DECOY_SCALE: f64 = 0.45;
+const DECOY_MAX_OPACITY: f64 = 0.3;
+fn decoy_half_extent(canvas_opts: &CanvasOpts) -> f64 {
+    canvas_opts.font_size * DECOY_SCALE / 2_f64 * SQRT_2
+}
+// 以`point`为中心绘制一个诱饵字符。`NoiseShapeKind::Glyph`背景图案也由它绘制。
+fn draw_decoy(context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point) -> Result<(), CheckCodeError> {
+    let char = canvas_opts.charset[OsRng.gen_range(0..canvas_opts.charset.len())];
+    let opacity = OsRng.gen_range(0.12_f64..DECOY_MAX_OPACITY);
+    context.save();
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_font(&format!("normal normal normal {}px Arial", canvas_opts.font_size * DECOY_SCALE)[..]);
+    context.translate(point.0, point.1)?;
+    context.rotate(OsRng.gen_range(-30_f64..30_f64).to_radians())?;
+    context.set_fill_style(&format!("rgba(80, 80, 80, {opacity:.2})").into());
+    context.fill_text(&char.to_string()[..], 0_f64, 0_f64)?;
+    context.restore();
+    Ok(())
+}
+fn draw_decoys(context: &dyn Context2d, canvas_opts: &CanvasOpts, glyphs: &[GlyphBox]) -> Result<usize, CheckCodeError> {
+    let half_extent = decoy_half_extent(canvas_opts);
+    let points = PoissonDisk::new(
+        half_extent,
+        half_extent,
+        canvas_opts.width - half_extent,
+        canvas_opts.height - half_extent,
+        half_extent * 2_f64
+    ).sample(canvas_opts.decoy_count as usize, |point| glyphs.iter().all(|glyph| !glyph.overlaps(point, half_extent)));
+    for point in &points {
+        draw_decoy(context, canvas_opts, point)?;
+    }
+    Ok(points.len())
+}
+// 在拼图块画布的中心绘制一个被随机旋转的字符，并返回把它转正所需的顺时针旋转角度
+fn draw_rotated_glyph(context: &dyn Context2d, canvas_opts: &CanvasOpts) -> Result<f64, CheckCodeError> {
+    // 仅挑选转了任意角度之后也不会被认作其它字符（或它自己）的字符。所以，成对相像的字符只保留其一：
+    // `C`转`90`度像`U`，`L`转半圈像`7`，`A`转半圈像`V`。
+    const GLYPHS: [char; 19] = [
+        'A', 'B', 'C', 'D', 'E', 'F', 'G', 'J', 'K', 'P',
+        'Q', 'R', 'T', 'Y', '2', '3', '4', '5', '7'
+    ];
+    let center = Point(canvas_opts.width / 2_f64, canvas_opts.height / 2_f64);
+    let radius = canvas_opts.height * 0.45;
+    context.clear_rect(0_f64, 0_f64, canvas_opts.width, canvas_opts.height);
+    context.save();
+    context.set_fill_style(&"rgba(255, 255, 255, 0.85)".into());
+    context.begin_path();
+    context.arc(center.0, center.1, radius, 0_f64, PI * 2_f64)?;
+    context.fill();
+    context.restore();
+    // 过于接近正立的角度一眼就能被蒙对，所以被排除在外
+    let degree = OsRng.gen_range(30_f64..330_f64);
+    draw_text(context, &[GlyphBox {
+        char: GLYPHS[OsRng.gen_range(0..GLYPHS.len())],
+        center,
+        half_extent: radius,
+        degree,
+        color: "rgba(0, 0, 0, 1)",
+        font: format!("normal normal bolder {}px Arial", radius * 1.4)
+    }])?;
+    Ok(360_f64 - degree)
+}
+// 在点选位置上标注其序号
+pub fn draw_click_mark(context: &dyn Context2d, point: &Point, ordinal: usize) -> Result<(), CheckCodeError> {
+    const RADIUS: f64 = 8_f64;
+    context.save();
+    context.set_fill_style(&"rgba(0, 102, 204, 0.85)".into());
+    context.begin_path();
+    context.arc(point.0, point.1, RADIUS, 0_f64, PI * 2_f64)?;
+    context.fill();
+    context.set_fill_style(&"rgba(255, 255, 255, 1)".into());
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_font(&format!("normal normal bold {}px Arial", RADIUS * 1.5)[..]);
+    context.fill_text(&ordinal.to_string()[..], point.0, point.1)?;
+    context.restore();
+    Ok(())
+}
@@ -1,34 +1,123 @@
 use ::std::fmt::Display;
-use ::wasm_bindgen::JsValue;
-use ::web_sys::{HtmlCanvasElement, Window};
-use crate::Props;
+use ::web_sys::{Element, HtmlCanvasElement, Window};
+use crate::{ChallengeMode, CHARS, CheckCodeError, Language, Props};
+use super::{CssLength, NoiseShapeKind};
+/// 画布尺寸的取值方式
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Sizing {
+    /// 仅采用`Props::width`与`Props::height`，忽略样式表
+    PropsOnly,
+    /// 仅采用样式表给出的尺寸
+    CssOnly,
+    /// 优先采用样式表给出的尺寸；样式表未设置的那一边，采用`Props`的值
+    #[default]
+    CssThenProps,
+    /// 宽度同`CssThenProps`，高度则按`Props::height / Props::width`的宽高比演算
+    AspectRatio
+}
+/// 干扰强度。经由`CheckCodeController::set_difficulty(..)`设置之后，它会覆盖`Props`的`star_count`、`noise_shapes`与`decoy_count`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    /// 与`Props`的缺省值相同
+    Normal,
+    Hard
+}
+impl Difficulty {
+    pub(crate) fn apply(&self, canvas_opts: &mut CanvasOpts) {
+        let (star_count, noise_shapes, decoy_count) = match self {
+            Difficulty::Easy => (10, vec![(NoiseShapeKind::Circle, 1)], 0),
+            Difficulty::Normal => (25, vec![(NoiseShapeKind::Star, 1)], 0),
+            Difficulty::Hard => (50, vec![
+                (NoiseShapeKind::Star, 2),
+                (NoiseShapeKind::Circle, 1),
+                (NoiseShapeKind::Polygon, 1),
+                (NoiseShapeKind::Segment, 2),
+                (NoiseShapeKind::Glyph, 1)
+            ], 4)
+        };
+        canvas_opts.star_count = star_count;
+        canvas_opts.noise_shapes = noise_shapes;
+        canvas_opts.decoy_count = decoy_count;
+    }
+}
 #[derive(Debug)]
 pub struct CanvasOpts {
-    pub width: f64,
-    pub height: f64,
-    pub star_size: f64,
-    pub star_count: u8,
-    pub font_size: f64
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) star_size: f64,
+    pub(crate) star_count: u32,
+    pub(crate) noise_shapes: Vec<(NoiseShapeKind, u8)>,
+    pub(crate) font_size: f64,
+    pub(crate) decoy_count: u32,
+    pub(crate) charset: Vec<char>,
+    pub(crate) challenge_mode: ChallengeMode
 }
 impl CanvasOpts {
-    pub fn with_canvas(window: &Window, canvas: &HtmlCanvasElement, props: &Props) -> Result<Self, JsValue> {
-        let parse_int_builder = |default: f64| move |str: String| -> f64 {
-            if str.is_empty() {
-                return default;
+    pub fn builder() -> CanvasOptsBuilder {
+        CanvasOptsBuilder::default()
+    }
+    pub fn with_canvas(window: &Window, canvas: &HtmlCanvasElement, props: &Props) -> Result<Self, CheckCodeError> {
+        let (canvas_width, canvas_height) = match props.sizing {
+            Sizing::PropsOnly => (props.width, props.height),
+            sizing => {
+                // 撤销上一次绘制时钉在行内样式上的 CSS 尺寸，以便重新测量样式表给出的尺寸。
+                let style = canvas.style();
+                style.remove_property("width")?;
+                style.remove_property("height")?;
+                match sizing {
+                    Sizing::CssOnly => (
+                        measure_css_only(window, canvas, "width", |width| canvas.set_width(width))?,
+                        measure_css_only(window, canvas, "height", |height| canvas.set_height(height))?
+                    ),
+                    _ => {
+                        // 重置画布的固有尺寸：若样式表未设置尺寸，则测量结果就是该固有尺寸。
+                        canvas.set_width(props.width.round().max(0_f64) as u32);
+                        canvas.set_height(props.height.round().max(0_f64) as u32);
+                        let css_width = measure_length(window, canvas, "width")?;
+                        let css_height = measure_length(window, canvas, "height")?;
+                        match sizing {
+                            Sizing::AspectRatio => {
+                                let width = css_width.unwrap_or(props.width);
+                                (width, width * props.height / props.width)
+                            },
+                            _ => (css_width.unwrap_or(props.width), css_height.unwrap_or(props.height))
+                        }
+                    }
+                }
             }
-            let str = str.find(char::is_alphabetic).map_or(&str[..], |index| &str[0..index]);
-            str.parse::<f64>().map_or(default, |v| v)
         };
-        let styles = window.get_computed_style(canvas)?.ok_or("浏览器不支持 CssStyleDeclaration")?;
-        let canvas_width = styles.get_property_value("width").map(parse_int_builder(props.width))?;
-        let canvas_height = styles.get_property_value("height").map(parse_int_builder(props.height))?;
-        Ok(CanvasOpts {
-            width: canvas_width,
-            height: canvas_height,
-            star_size: props.star_size,
-            star_count: props.star_count,
-            font_size: props.font_size,
-        })
+        Ok(CanvasOpts::builder()
+            .width(canvas_width)
+            .height(canvas_height)
+            .star_size(props.star_size)
+            .star_count(props.star_count)
+            .noise_shapes(props.noise_shapes.clone())
+            .font_size(props.font_size)
+            .decoy_count(props.decoy_count)
+            .check_code_len(props.check_code_len)
+            .charset(props.charset())
+            .challenge_mode(props.mode)
+            .build()?)
+    }
+    // 只读访问器，以便自定义的`NoiseShape`按画布配置绘制
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+    pub fn star_size(&self) -> f64 {
+        self.star_size
+    }
+    pub fn font_size(&self) -> f64 {
+        self.font_size
+    }
+    pub fn charset(&self) -> &[char] {
+        &self.charset[..]
+    }
+    pub fn challenge_mode(&self) -> ChallengeMode {
+        self.challenge_mode
     }
     pub fn viewport_width(&self) -> f64 {
         self.width - self.star_size * 2_f64
@@ -37,8 +126,184 @@ impl CanvasOpts {
         self.height - self.star_size * 2_f64
     }
 }
+// 未被样式表设置尺寸的画布也会被测得其固有尺寸（缺省是`300 x 150`），所以只能换着两个固有尺寸各测量一次：
+// 测量结果随固有尺寸而变，就说明样式表未给出该边的尺寸。
+fn measure_css_only(window: &Window, canvas: &HtmlCanvasElement, property: &str, set_intrinsic: impl Fn(u32)) -> Result<f64, CheckCodeError> {
+    const PROBES: [u32; 2] = [300, 400];
+    let mut lengths = [0_f64; 2];
+    for (probe, length) in PROBES.into_iter().zip(&mut lengths) {
+        set_intrinsic(probe);
+        *length = measure_length(window, canvas, property)?.ok_or(CheckCodeError::MissingCssSize)?;
+    }
+    if (lengths[0] - lengths[1]).abs() >= 0.5 {
+        return Err(CheckCodeError::MissingCssSize);
+    }
+    Ok(lengths[0])
+}
+// 测量元素的计算样式尺寸，并换算为像素值。样式值为空或`auto`（比如，元素尚未被渲染）时，返回`None`。
+fn measure_length(window: &Window, element: &Element, property: &str) -> Result<Option<f64>, CheckCodeError> {
+    let styles = window.get_computed_style(element)?.ok_or(CheckCodeError::CssUnavailable)?;
+    let value = styles.get_property_value(property)?;
+    if value.is_empty() || value == "auto" {
+        return Ok(None);
+    }
+    let length = CssLength::parse(&value[..])?;
+    if let CssLength::Px(px) = length {
+        return Ok(Some(px));
+    }
+    let font_size = measure_length(window, element, "font-size")?.unwrap_or(16_f64);
+    let root_font_size = match window.document().and_then(|document| document.document_element()) {
+        Some(root) => measure_length(window, &root, "font-size")?.unwrap_or(16_f64),
+        None => 16_f64
+    };
+    let parent_size = match element.parent_element() {
+        Some(parent) => measure_length(window, &parent, property)?.ok_or_else(|| CheckCodeError::UnsupportedCssLength(value.clone()))?,
+        None => return Err(CheckCodeError::UnsupportedCssLength(value))
+    };
+    Ok(Some(length.to_px(font_size, root_font_size, parent_size)))
+}
 impl Display for CanvasOpts {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:#?}", self)
     }
-}
\ No newline at end of file
+}
+/// 带校验的`CanvasOpts`构建器。各项缺省值与`Props`的缺省值相同。
+#[derive(Clone, Debug)]
+pub struct CanvasOptsBuilder {
+    width: f64,
+    height: f64,
+    star_size: f64,
+    star_count: u32,
+    noise_shapes: Vec<(NoiseShapeKind, u8)>,
+    font_size: f64,
+    decoy_count: u32,
+    check_code_len: u16,
+    charset: Vec<char>,
+    challenge_mode: ChallengeMode
+}
+impl Default for CanvasOptsBuilder {
+    fn default() -> Self {
+        CanvasOptsBuilder {
+            width: 150_f64,
+            height: 50_f64,
+            star_size: 7_f64,
+            star_count: 25,
+            noise_shapes: vec![(NoiseShapeKind::Star, 1)],
+            font_size: 22_f64,
+            decoy_count: 0,
+            check_code_len: 5,
+            charset: CHARS.to_vec(),
+            challenge_mode: ChallengeMode::default()
+        }
+    }
+}
+impl CanvasOptsBuilder {
+    setters!(
+        width: f64,
+        height: f64,
+        star_size: f64,
+        star_count: u32,
+        noise_shapes: Vec<(NoiseShapeKind, u8)>,
+        font_size: f64,
+        decoy_count: u32,
+        check_code_len: u16,
+        charset: Vec<char>,
+        challenge_mode: ChallengeMode
+    );
+    pub fn build(self) -> Result<CanvasOpts, OptionsError> {
+        if !self.width.is_finite() || self.width <= 0_f64 {
+            return Err(OptionsError::InvalidWidth(self.width));
+        }
+        if !self.height.is_finite() || self.height <= 0_f64 {
+            return Err(OptionsError::InvalidHeight(self.height));
+        }
+        if !self.star_size.is_finite() || self.star_size < 0_f64 {
+            return Err(OptionsError::InvalidStarSize(self.star_size));
+        }
+        // 背景图案与验证码字符都只被绘制在扣除了两侧`star_size`留白的视口内
+        if self.star_size * 2_f64 >= self.width.min(self.height) {
+            return Err(OptionsError::StarSizeTooLarge {
+                star_size: self.star_size,
+                width: self.width,
+                height: self.height
+            });
+        }
+        if !self.font_size.is_finite() || self.font_size <= 0_f64 {
+            return Err(OptionsError::InvalidFontSize(self.font_size));
+        }
+        if self.check_code_len == 0 {
+            return Err(OptionsError::ZeroCheckCodeLen);
+        }
+        if self.charset.is_empty() {
+            return Err(OptionsError::EmptyCharset);
+        }
+        // 点选模式按字符作答，所以验证码内的字符不能重复
+        if self.challenge_mode == ChallengeMode::ClickOrder {
+            let mut distinct = self.charset.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if usize::from(self.check_code_len) > distinct.len() {
+                return Err(OptionsError::CheckCodeTooLong {
+                    check_code_len: self.check_code_len,
+                    charset_len: distinct.len()
+                });
+            }
+        }
+        Ok(CanvasOpts {
+            width: self.width,
+            height: self.height,
+            star_size: self.star_size,
+            star_count: self.star_count,
+            noise_shapes: self.noise_shapes,
+            font_size: self.font_size,
+            decoy_count: self.decoy_count,
+            charset: self.charset,
+            challenge_mode: self.challenge_mode
+        })
+    }
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionsError {
+    InvalidWidth(f64),
+    InvalidHeight(f64),
+    InvalidStarSize(f64),
+    StarSizeTooLarge {
+        star_size: f64,
+        width: f64,
+        height: f64
+    },
+    InvalidFontSize(f64),
+    ZeroCheckCodeLen,
+    EmptyCharset,
+    CheckCodeTooLong {
+        check_code_len: u16,
+        charset_len: usize
+    }
+}
+impl OptionsError {
+    pub fn localize(&self, language: Language) -> String {
+        match (self, language) {
+            (OptionsError::InvalidWidth(width), Language::Zh) => format!("画布宽度必须是正数，实际是 {width}"),
+            (OptionsError::InvalidWidth(width), Language::En) => format!("The canvas width must be positive, got {width}"),
+            (OptionsError::InvalidHeight(height), Language::Zh) => format!("画布高度必须是正数，实际是 {height}"),
+            (OptionsError::InvalidHeight(height), Language::En) => format!("The canvas height must be positive, got {height}"),
+            (OptionsError::InvalidStarSize(star_size), Language::Zh) => format!("背景图案尺寸不能是负数，实际是 {star_size}"),
+            (OptionsError::InvalidStarSize(star_size), Language::En) => format!("The star size must not be negative, got {star_size}"),
+            (OptionsError::StarSizeTooLarge {star_size, width, height}, Language::Zh) => format!("背景图案尺寸 {star_size} 必须小于画布 {width} x {height} 短边的一半"),
+            (OptionsError::StarSizeTooLarge {star_size, width, height}, Language::En) => format!("The star size {star_size} must be less than half of the shorter side of the {width} x {height} canvas"),
+            (OptionsError::InvalidFontSize(font_size), Language::Zh) => format!("字号必须是正数，实际是 {font_size}"),
+            (OptionsError::InvalidFontSize(font_size), Language::En) => format!("The font size must be positive, got {font_size}"),
+            (OptionsError::ZeroCheckCodeLen, Language::Zh) => "验证码至少要有 1 个字符".to_string(),
+            (OptionsError::ZeroCheckCodeLen, Language::En) => "The check code needs at least 1 character".to_string(),
+            (OptionsError::EmptyCharset, Language::Zh) => "验证码字符集不能为空".to_string(),
+            (OptionsError::EmptyCharset, Language::En) => "The check code charset must not be empty".to_string(),
+            (OptionsError::CheckCodeTooLong {check_code_len, charset_len}, Language::Zh) => format!("点选模式的验证码字符互不重复，所以其长度 {check_code_len} 不能超过字符集内不同字符的个数 {charset_len}"),
+            (OptionsError::CheckCodeTooLong {check_code_len, charset_len}, Language::En) => format!("Click-order check codes have no repeated characters, so the length {check_code_len} must not exceed the {charset_len} distinct characters of the charset")
+        }
+    }
+}
+impl Display for OptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.localize(Language::default())[..])
+    }
+}
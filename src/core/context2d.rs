@@ -0,0 +1,177 @@
+use ::wasm_bindgen::{JsCast, JsValue};
+use ::web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, OffscreenCanvas, OffscreenCanvasRenderingContext2d, TextMetrics};
+use crate::CheckCodeError;
+/// 图形验证码的绘制目标：页面上的`<canvas>`元素，或`Web Worker`内的`OffscreenCanvas`
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderTarget {
+    Canvas(HtmlCanvasElement),
+    Offscreen(OffscreenCanvas)
+}
+impl RenderTarget {
+    pub fn width(&self) -> u32 {
+        match self {
+            RenderTarget::Canvas(canvas) => canvas.width(),
+            RenderTarget::Offscreen(canvas) => canvas.width()
+        }
+    }
+    pub fn height(&self) -> u32 {
+        match self {
+            RenderTarget::Canvas(canvas) => canvas.height(),
+            RenderTarget::Offscreen(canvas) => canvas.height()
+        }
+    }
+    pub(crate) fn set_size(&self, width: u32, height: u32) {
+        match self {
+            RenderTarget::Canvas(canvas) => {
+                canvas.set_width(width);
+                canvas.set_height(height);
+            },
+            RenderTarget::Offscreen(canvas) => {
+                canvas.set_width(width);
+                canvas.set_height(height);
+            }
+        }
+    }
+    pub(crate) fn context(&self) -> Result<Box<dyn Context2d>, CheckCodeError> {
+        let context = match self {
+            RenderTarget::Canvas(canvas) => canvas.get_context("2d")?,
+            RenderTarget::Offscreen(canvas) => canvas.get_context("2d")?
+        }.ok_or(CheckCodeError::No2dContext)?;
+        Ok(match self {
+            RenderTarget::Canvas(_) => Box::new(context.dyn_into::<CanvasRenderingContext2d>().map_err(|_| CheckCodeError::No2dContext)?),
+            RenderTarget::Offscreen(_) => Box::new(context.dyn_into::<OffscreenCanvasRenderingContext2d>().map_err(|_| CheckCodeError::No2dContext)?)
+        })
+    }
+}
+impl From<HtmlCanvasElement> for RenderTarget {
+    fn from(canvas: HtmlCanvasElement) -> Self {
+        RenderTarget::Canvas(canvas)
+    }
+}
+impl From<OffscreenCanvas> for RenderTarget {
+    fn from(canvas: OffscreenCanvas) -> Self {
+        RenderTarget::Offscreen(canvas)
+    }
+}
+/// 绘制图形验证码所用到的`Canvas 2D`渲染上下文方法。`CanvasRenderingContext2d`与`OffscreenCanvasRenderingContext2d`
+/// 都实现了它，所以同一套绘制代码既能在页面上、也能在`Web Worker`内运行。
+pub trait Context2d {
+    fn save(&self);
+    fn restore(&self);
+    fn set_fill_style(&self, value: &JsValue);
+    fn set_stroke_style(&self, value: &JsValue);
+    fn set_line_width(&self, value: f64);
+    fn set_line_cap(&self, value: &str);
+    fn set_font(&self, value: &str);
+    fn set_text_align(&self, value: &str);
+    fn set_text_baseline(&self, value: &str);
+    fn begin_path(&self);
+    fn close_path(&self);
+    fn move_to(&self, x: f64, y: f64);
+    fn line_to(&self, x: f64, y: f64);
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) -> Result<(), JsValue>;
+    fn fill(&self);
+    fn stroke(&self);
+    fn clip(&self);
+    fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64);
+    fn clear_rect(&self, x: f64, y: f64, w: f64, h: f64);
+    fn fill_text(&self, text: &str, x: f64, y: f64) -> Result<(), JsValue>;
+    fn measure_text(&self, text: &str) -> Result<TextMetrics, JsValue>;
+    fn translate(&self, x: f64, y: f64) -> Result<(), JsValue>;
+    fn rotate(&self, angle: f64) -> Result<(), JsValue>;
+    fn set_transform(&self, a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Result<(), JsValue>;
+    /// 本上下文所属的画布
+    fn target(&self) -> Option<RenderTarget>;
+    /// 把`source`画布上`[x, y, w, h]`的区域复制到本画布的`[x, y, w, h]`区域
+    fn draw_region(&self, source: &RenderTarget, from: [f64; 4], to: [f64; 4]) -> Result<(), JsValue>;
+}
+// 两种渲染上下文的同名方法签名相同，所以逐一委托给其固有方法
+macro_rules! impl_context_2d {
+    ($type: ty, $target: expr) => {
+        impl Context2d for $type {
+            fn save(&self) {
+                <$type>::save(self)
+            }
+            fn restore(&self) {
+                <$type>::restore(self)
+            }
+            fn set_fill_style(&self, value: &JsValue) {
+                <$type>::set_fill_style(self, value)
+            }
+            fn set_stroke_style(&self, value: &JsValue) {
+                <$type>::set_stroke_style(self, value)
+            }
+            fn set_line_width(&self, value: f64) {
+                <$type>::set_line_width(self, value)
+            }
+            fn set_line_cap(&self, value: &str) {
+                <$type>::set_line_cap(self, value)
+            }
+            fn set_font(&self, value: &str) {
+                <$type>::set_font(self, value)
+            }
+            fn set_text_align(&self, value: &str) {
+                <$type>::set_text_align(self, value)
+            }
+            fn set_text_baseline(&self, value: &str) {
+                <$type>::set_text_baseline(self, value)
+            }
+            fn begin_path(&self) {
+                <$type>::begin_path(self)
+            }
+            fn close_path(&self) {
+                <$type>::close_path(self)
+            }
+            fn move_to(&self, x: f64, y: f64) {
+                <$type>::move_to(self, x, y)
+            }
+            fn line_to(&self, x: f64, y: f64) {
+                <$type>::line_to(self, x, y)
+            }
+            fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) -> Result<(), JsValue> {
+                <$type>::arc(self, x, y, radius, start_angle, end_angle)
+            }
+            fn fill(&self) {
+                <$type>::fill(self)
+            }
+            fn stroke(&self) {
+                <$type>::stroke(self)
+            }
+            fn clip(&self) {
+                <$type>::clip(self)
+            }
+            fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+                <$type>::fill_rect(self, x, y, w, h)
+            }
+            fn clear_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+                <$type>::clear_rect(self, x, y, w, h)
+            }
+            fn fill_text(&self, text: &str, x: f64, y: f64) -> Result<(), JsValue> {
+                <$type>::fill_text(self, text, x, y)
+            }
+            fn measure_text(&self, text: &str) -> Result<TextMetrics, JsValue> {
+                <$type>::measure_text(self, text)
+            }
+            fn translate(&self, x: f64, y: f64) -> Result<(), JsValue> {
+                <$type>::translate(self, x, y)
+            }
+            fn rotate(&self, angle: f64) -> Result<(), JsValue> {
+                <$type>::rotate(self, angle)
+            }
+            fn set_transform(&self, a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Result<(), JsValue> {
+                <$type>::set_transform(self, a, b, c, d, e, f)
+            }
+            fn target(&self) -> Option<RenderTarget> {
+                ($target)(self)
+            }
+            fn draw_region(&self, source: &RenderTarget, [sx, sy, sw, sh]: [f64; 4], [dx, dy, dw, dh]: [f64; 4]) -> Result<(), JsValue> {
+                match source {
+                    RenderTarget::Canvas(canvas) => self.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(canvas, sx, sy, sw, sh, dx, dy, dw, dh),
+                    RenderTarget::Offscreen(canvas) => self.draw_image_with_offscreen_canvas_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(canvas, sx, sy, sw, sh, dx, dy, dw, dh)
+                }
+            }
+        }
+    };
+}
+impl_context_2d!(CanvasRenderingContext2d, |context: &CanvasRenderingContext2d| context.canvas().map(RenderTarget::Canvas));
+impl_context_2d!(OffscreenCanvasRenderingContext2d, |context: &OffscreenCanvasRenderingContext2d| Some(RenderTarget::Offscreen(context.canvas())));
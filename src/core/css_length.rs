@@ -0,0 +1,73 @@
+use crate::CheckCodeError;
+/// `CSS`长度值。仅支持`px`、`em`、`rem`与`%`四种单位，其它单位（包括`calc(..)`等函数表达式）都被视为错误，而不是被猜测。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CssLength {
+    Px(f64),
+    Em(f64),
+    Rem(f64),
+    Percent(f64)
+}
+impl CssLength {
+    pub fn parse(value: &str) -> Result<Self, CheckCodeError> {
+        let unsupported = || CheckCodeError::UnsupportedCssLength(value.to_string());
+        let trimmed = value.trim();
+        let number_len = scan_number(trimmed).ok_or_else(unsupported)?;
+        let number = trimmed[..number_len].parse::<f64>().map_err(|_| unsupported())?;
+        match &trimmed[number_len..].to_ascii_lowercase()[..] {
+            "px" => Ok(CssLength::Px(number)),
+            "em" => Ok(CssLength::Em(number)),
+            "rem" => Ok(CssLength::Rem(number)),
+            "%" => Ok(CssLength::Percent(number)),
+            // 仅`0`可以省略单位
+            "" if number == 0_f64 => Ok(CssLength::Px(0_f64)),
+            _ => Err(unsupported())
+        }
+    }
+    /// 换算成像素值。`em`相对于元素自身的字号，`rem`相对于根元素的字号，`%`相对于父元素的同向尺寸。
+    pub fn to_px(&self, font_size: f64, root_font_size: f64, parent_size: f64) -> f64 {
+        match *self {
+            CssLength::Px(value) => value,
+            CssLength::Em(value) => value * font_size,
+            CssLength::Rem(value) => value * root_font_size,
+            CssLength::Percent(value) => value / 100_f64 * parent_size
+        }
+    }
+}
+// 返回`value`开头的`CSS <number>`的字节长度：[+-]?(\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?
+fn scan_number(value: &str) -> Option<usize> {
+    let bytes = value.as_bytes();
+    let digits_from = |mut index: usize| {
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        index
+    };
+    let mut index = 0_usize;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        index += 1;
+    }
+    let integer_end = digits_from(index);
+    let mut mantissa_end = integer_end;
+    if bytes.get(integer_end) == Some(&b'.') {
+        let fraction_end = digits_from(integer_end + 1);
+        if fraction_end > integer_end + 1 {
+            mantissa_end = fraction_end;
+        }
+    }
+    if mantissa_end == index {
+        return None;
+    }
+    index = mantissa_end;
+    if matches!(bytes.get(index), Some(b'e' | b'E')) {
+        let mut exponent = index + 1;
+        if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+            exponent += 1;
+        }
+        let exponent_end = digits_from(exponent);
+        // `e`之后没有数字，说明它是单位（比如，`em`）的开头
+        if exponent_end > exponent {
+            index = exponent_end;
+        }
+    }
+    Some(index)
+}
@@ -0,0 +1,146 @@
+use ::rand::{Rng, rngs::OsRng};
+use ::std::{f64::consts::PI, fmt, rc::Rc};
+use crate::CheckCodeError;
+use super::{CanvasOpts, Context2d, Point};
+/// 背景干扰图案。每种图案都以`point`为中心、以`canvas_opts.star_size()`为外接圆半径绘制。
+/// 实现了它的自定义图案可经由`NoiseShapeKind::Custom`混入`noise_shapes`。
+pub trait NoiseShape {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, degree: f64, rgba: &str) -> Result<(), CheckCodeError>;
+    /// 形似字符的图案须像诱饵字符一样远离验证码字符，以免被误认作答案。其包围盒的半边长与诱饵字符相同。
+    fn mimics_text(&self) -> bool {
+        false
+    }
+}
+#[derive(Clone)]
+pub enum NoiseShapeKind {
+    Star,
+    Circle,
+    Polygon,
+    Segment,
+    Glyph,
+    Custom(Rc<dyn NoiseShape>)
+}
+impl NoiseShapeKind {
+    pub fn shape(&self) -> &dyn NoiseShape {
+        match self {
+            NoiseShapeKind::Star => &Star,
+            NoiseShapeKind::Circle => &Circle,
+            NoiseShapeKind::Polygon => &Polygon,
+            NoiseShapeKind::Segment => &Segment,
+            NoiseShapeKind::Glyph => &Glyph,
+            NoiseShapeKind::Custom(shape) => &**shape
+        }
+    }
+}
+// 自定义图案仅当是同一个`Rc`时才相等
+impl PartialEq for NoiseShapeKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NoiseShapeKind::Custom(shape1), NoiseShapeKind::Custom(shape2)) => Rc::ptr_eq(shape1, shape2),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other)
+        }
+    }
+}
+impl fmt::Debug for NoiseShapeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoiseShapeKind::Star => f.write_str("Star"),
+            NoiseShapeKind::Circle => f.write_str("Circle"),
+            NoiseShapeKind::Polygon => f.write_str("Polygon"),
+            NoiseShapeKind::Segment => f.write_str("Segment"),
+            NoiseShapeKind::Glyph => f.write_str("Glyph"),
+            NoiseShapeKind::Custom(shape) => f.debug_tuple("Custom").field(&Rc::as_ptr(shape)).finish()
+        }
+    }
+}
+struct Star;
+impl NoiseShape for Star {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, degree: f64, rgba: &str) -> Result<(), CheckCodeError> {
+        let corner_count = 3_u8 + OsRng.gen_range(0_u8..5_u8);
+        let long_radius = canvas_opts.star_size;
+        let short_radius = long_radius / 2.5;
+        let c1 = 360_f64 / corner_count as f64;
+        let c2 = 90_f64 / corner_count as f64;
+        let c3 = 270_f64 / corner_count as f64;
+        context.save();
+        context.translate(point.0, point.1)?;
+        context.rotate(degree.to_radians())?;
+        context.set_fill_style(&rgba.into());
+        context.begin_path();
+        for i in 0..corner_count {
+            let i = i as f64;
+            context.line_to(
+                (c2 + i * c1).to_radians().cos() * long_radius,
+                (c2 + i * c1).to_radians().sin() * long_radius
+            );
+            context.line_to(
+                (c3 + i * c1).to_radians().cos() * short_radius,
+                (c3 + i * c1).to_radians().sin() * short_radius
+            );
+        }
+        context.close_path();
+        context.fill();
+        context.restore();
+        Ok(())
+    }
+}
+struct Circle;
+impl NoiseShape for Circle {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, _degree: f64, rgba: &str) -> Result<(), CheckCodeError> {
+        let radius = canvas_opts.star_size * OsRng.gen_range(0.5_f64..1_f64);
+        context.save();
+        context.set_fill_style(&rgba.into());
+        context.begin_path();
+        context.arc(point.0, point.1, radius, 0_f64, PI * 2_f64)?;
+        context.fill();
+        context.restore();
+        Ok(())
+    }
+}
+struct Polygon;
+impl NoiseShape for Polygon {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, degree: f64, rgba: &str) -> Result<(), CheckCodeError> {
+        let side_count = 3_u8 + OsRng.gen_range(0_u8..4_u8);
+        let step = 360_f64 / side_count as f64;
+        context.save();
+        context.translate(point.0, point.1)?;
+        context.rotate(degree.to_radians())?;
+        context.set_fill_style(&rgba.into());
+        context.begin_path();
+        for i in 0..side_count {
+            let radian = (i as f64 * step).to_radians();
+            context.line_to(radian.cos() * canvas_opts.star_size, radian.sin() * canvas_opts.star_size);
+        }
+        context.close_path();
+        context.fill();
+        context.restore();
+        Ok(())
+    }
+}
+struct Segment;
+impl NoiseShape for Segment {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, degree: f64, rgba: &str) -> Result<(), CheckCodeError> {
+        context.save();
+        context.translate(point.0, point.1)?;
+        context.rotate(degree.to_radians())?;
+        context.set_stroke_style(&rgba.into());
+        context.set_line_width((canvas_opts.star_size / 4_f64).max(1_f64));
+        context.set_line_cap("round");
+        context.begin_path();
+        context.move_to(-canvas_opts.star_size, 0_f64);
+        context.line_to(canvas_opts.star_size, 0_f64);
+        context.stroke();
+        context.restore();
+        Ok(())
+    }
+}
+// 字符图案就是诱饵字符，所以其字号、不透明度与旋转角度都遵循诱饵字符的规则，而忽略`degree`与`rgba`
+struct Glyph;
+impl NoiseShape for Glyph {
+    fn draw(&self, context: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, _degree: f64, _rgba: &str) -> Result<(), CheckCodeError> {
+        super::draw_decoy(context, canvas_opts, point)
+    }
+    fn mimics_text(&self) -> bool {
+        true
+    }
+}
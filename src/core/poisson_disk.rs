@@ -0,0 +1,105 @@
+use ::rand::{Rng, rngs::OsRng, seq::SliceRandom};
+use ::std::f64::consts::{PI, SQRT_2};
+use super::Point;
+/// Bridson 泊松圆盘采样。见：https://www.cs.ubc.ca/~rbridson/docs/bridson-siggraph07-poissondisk.pdf
+///
+/// 在`[left, right] x [top, bottom]`矩形区域内，演算出任意两点间距都不小于`radius`的（近似）最大点集，
+/// 再从中随机挑出至多`limit`个点。借助边长为`radius / √2`的网格做空间索引，每个候选点仅需与周边 5 x 5 个网格内的点比较距离。
+/// `accept`可额外排除某些区域（比如，验证码字符的包围盒）。
+pub struct PoissonDisk {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+    radius: f64,
+    cell_size: f64,
+    cols: usize,
+    rows: usize,
+    grid: Vec<Option<usize>>,
+    points: Vec<Point>
+}
+impl PoissonDisk {
+    // 每个活跃点周围的最多尝试次数
+    const CANDIDATE_COUNT: u8 = 30;
+    // 活跃点耗尽以后，为覆盖被`accept`隔断的孤立区域，再随机补种的最多尝试次数
+    const RESEED_COUNT: u8 = 30;
+    pub fn new(left: f64, top: f64, right: f64, bottom: f64, radius: f64) -> Self {
+        let radius = radius.max(1_f64);
+        let cell_size = radius / SQRT_2;
+        let cols = ((right - left).max(0_f64) / cell_size).ceil() as usize + 1;
+        let rows = ((bottom - top).max(0_f64) / cell_size).ceil() as usize + 1;
+        PoissonDisk {
+            left, top, right, bottom, radius, cell_size, cols, rows,
+            grid: vec![None; cols * rows],
+            points: vec![]
+        }
+    }
+    pub fn sample(mut self, limit: usize, accept: impl Fn(&Point) -> bool) -> Vec<Point> {
+        if limit == 0 || self.right < self.left || self.bottom < self.top {
+            return vec![];
+        }
+        let mut active: Vec<usize> = vec![];
+        let mut reseed = 0_u8;
+        while reseed < Self::RESEED_COUNT {
+            if active.is_empty() {
+                let point = Point(self.gen_coord(self.left, self.right), self.gen_coord(self.top, self.bottom));
+                if accept(&point) && self.is_apart(&point) {
+                    active.push(self.insert(point));
+                } else {
+                    reseed += 1;
+                }
+                continue;
+            }
+            let active_index = OsRng.gen_range(0..active.len());
+            let Point(x, y) = self.points[active[active_index]];
+            let found = (0..Self::CANDIDATE_COUNT).map(|_| {
+                let radian = OsRng.gen_range(0_f64..PI * 2_f64);
+                let distance = OsRng.gen_range(self.radius..self.radius * 2_f64);
+                Point(x + radian.cos() * distance, y + radian.sin() * distance)
+            }).find(|point| self.contains(point) && accept(point) && self.is_apart(point));
+            match found {
+                Some(point) => active.push(self.insert(point)),
+                None => {
+                    active.swap_remove(active_index);
+                }
+            }
+        }
+        let mut points = self.points;
+        points.shuffle(&mut OsRng);
+        points.truncate(limit);
+        points
+    }
+    fn gen_coord(&self, min: f64, max: f64) -> f64 {
+        if max > min { OsRng.gen_range(min..=max) } else { min }
+    }
+    fn contains(&self, point: &Point) -> bool {
+        point.0 >= self.left && point.0 <= self.right && point.1 >= self.top && point.1 <= self.bottom
+    }
+    fn cell_of(&self, point: &Point) -> (usize, usize) {
+        (
+            (((point.0 - self.left) / self.cell_size) as usize).min(self.cols - 1),
+            (((point.1 - self.top) / self.cell_size) as usize).min(self.rows - 1)
+        )
+    }
+    fn is_apart(&self, point: &Point) -> bool {
+        let (col, row) = self.cell_of(point);
+        for r in row.saturating_sub(2)..(row + 3).min(self.rows) {
+            for c in col.saturating_sub(2)..(col + 3).min(self.cols) {
+                if let Some(index) = self.grid[r * self.cols + c] {
+                    let other = &self.points[index];
+                    if (other.0 - point.0).powi(2) + (other.1 - point.1).powi(2) < self.radius.powi(2) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+    fn insert(&mut self, point: Point) -> usize {
+        let (col, row) = self.cell_of(&point);
+        let index = self.points.len();
+        self.grid[row * self.cols + col] = Some(index);
+        self.points.push(point);
+        index
+    }
+}
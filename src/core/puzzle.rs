@@ -0,0 +1,67 @@
+use ::rand::{Rng, rngs::OsRng};
+use ::std::f64::consts::PI;
+use crate::{CheckCodeError, SLIDER_HANDLE_SIZE};
+use super::{CanvasOpts, Context2d};
+// 拼图块的边长占画布高度的比例。拼图块的上边与右边各有一个半径为边长 1/5 的凸起。
+const PIECE_RATIO: f64 = 0.4;
+/// 在`context`上挖出拼图缺口，再把缺口处的图案剪切到`piece_context`左侧的起始位置。两块画布尺寸相同、相互重叠，
+/// 所以把`piece_context`所在的画布向右平移返回值（单位是`CSS`像素）即可令拼图块与缺口对齐。
+pub fn draw_puzzle(context: &dyn Context2d, piece_context: &dyn Context2d, canvas_opts: &CanvasOpts) -> Result<f64, CheckCodeError> {
+    let size = canvas_opts.height * PIECE_RATIO;
+    let knob = size / 5_f64;
+    let start_x = canvas_opts.star_size;
+    let y = random_between(canvas_opts.star_size + knob, canvas_opts.height - size - canvas_opts.star_size);
+    // 缺口不能超出滑块的行程（滑轨与画布同宽，扣除滑块自身的边长）。画布太小时，上下界可能倒挂，此时取上界。
+    let high = (canvas_opts.width - size - knob - canvas_opts.star_size).min(start_x + canvas_opts.width - SLIDER_HANDLE_SIZE).max(start_x);
+    let low = (canvas_opts.width / 3_f64).max(start_x + size + knob).min(high);
+    let target_x = random_between(low, high);
+    let canvas = context.target().ok_or(CheckCodeError::No2dContext)?;
+    // 源图案取自放大了 devicePixelRatio 倍的像素缓冲区
+    let ratio = canvas.width() as f64 / canvas_opts.width;
+    piece_context.clear_rect(0_f64, 0_f64, canvas_opts.width, canvas_opts.height);
+    piece_context.save();
+    puzzle_path(piece_context, start_x, y, size)?;
+    piece_context.clip();
+    piece_context.draw_region(
+        &canvas,
+        [target_x * ratio, (y - knob) * ratio, (size + knob) * ratio, (size + knob) * ratio],
+        [start_x, y - knob, size + knob, size + knob]
+    )?;
+    piece_context.restore();
+    piece_context.save();
+    puzzle_path(piece_context, start_x, y, size)?;
+    piece_context.set_stroke_style(&"rgba(255, 255, 255, 0.9)".into());
+    piece_context.set_line_width(2_f64);
+    piece_context.stroke();
+    piece_context.restore();
+    context.save();
+    puzzle_path(context, target_x, y, size)?;
+    context.set_fill_style(&"rgba(0, 0, 0, 0.45)".into());
+    context.fill();
+    context.set_stroke_style(&"rgba(255, 255, 255, 0.8)".into());
+    context.stroke();
+    context.restore();
+    Ok(target_x - start_x)
+}
+fn random_between(low: f64, high: f64) -> f64 {
+    if high > low {
+        OsRng.gen_range(low..high)
+    } else {
+        low
+    }
+}
+// 以`(x, y)`为左上角、边长为`size`的拼图块轮廓
+fn puzzle_path(context: &dyn Context2d, x: f64, y: f64, size: f64) -> Result<(), CheckCodeError> {
+    let knob = size / 5_f64;
+    context.begin_path();
+    context.move_to(x, y);
+    context.line_to(x + size / 2_f64 - knob, y);
+    context.arc(x + size / 2_f64, y, knob, PI, PI * 2_f64)?;
+    context.line_to(x + size, y);
+    context.line_to(x + size, y + size / 2_f64 - knob);
+    context.arc(x + size, y + size / 2_f64, knob, PI * 1.5, PI * 0.5)?;
+    context.line_to(x + size, y + size);
+    context.line_to(x, y + size);
+    context.close_path();
+    Ok(())
+}
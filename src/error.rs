@@ -0,0 +1,57 @@
+use ::std::fmt::{self, Display};
+use ::wasm_bindgen::JsValue;
+use crate::{i18n::Language, OptionsError};
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckCodeError {
+    MissingCanvas,
+    No2dContext,
+    NoHeadElement,
+    CssUnavailable,
+    UnsupportedCssLength(String),
+    MissingCssSize,
+    InvalidOptions(OptionsError),
+    AudioUnavailable,
+    ExportFailure,
+    DrawFailure(String)
+}
+impl CheckCodeError {
+    pub fn localize(&self, language: Language) -> String {
+        match (self, language) {
+            (CheckCodeError::MissingCanvas, Language::Zh) => "未能获取 canvas 元素".to_string(),
+            (CheckCodeError::MissingCanvas, Language::En) => "The canvas element is not available".to_string(),
+            (CheckCodeError::No2dContext, Language::Zh) => "浏览器画布不支持 2D 渲染上下文".to_string(),
+            (CheckCodeError::No2dContext, Language::En) => "The browser canvas does not support a 2D rendering context".to_string(),
+            (CheckCodeError::NoHeadElement, Language::Zh) => "未运行于浏览器环境内，没有 head DOM 结点".to_string(),
+            (CheckCodeError::NoHeadElement, Language::En) => "Not running in a browser: there is no head element".to_string(),
+            (CheckCodeError::CssUnavailable, Language::Zh) => "浏览器不支持 CssStyleDeclaration".to_string(),
+            (CheckCodeError::CssUnavailable, Language::En) => "The browser does not support CssStyleDeclaration".to_string(),
+            (CheckCodeError::UnsupportedCssLength(value), Language::Zh) => format!("不支持的 CSS 长度值：{value}（仅支持 px、em、rem 与 %）"),
+            (CheckCodeError::UnsupportedCssLength(value), Language::En) => format!("Unsupported CSS length: {value} (only px, em, rem and % are supported)"),
+            (CheckCodeError::MissingCssSize, Language::Zh) => "样式表未给出画布的尺寸".to_string(),
+            (CheckCodeError::MissingCssSize, Language::En) => "The stylesheet does not give the canvas a size".to_string(),
+            (CheckCodeError::InvalidOptions(error), language) => error.localize(language),
+            (CheckCodeError::AudioUnavailable, Language::Zh) => "浏览器不支持语音合成或 Web Audio，无法播放语音验证码".to_string(),
+            (CheckCodeError::AudioUnavailable, Language::En) => "The browser cannot play the audio challenge: speech synthesis or Web Audio is unavailable".to_string(),
+            (CheckCodeError::ExportFailure, Language::Zh) => "导出验证码图片失败".to_string(),
+            (CheckCodeError::ExportFailure, Language::En) => "Failed to export the check code image".to_string(),
+            (CheckCodeError::DrawFailure(reason), Language::Zh) => format!("绘制图形验证码失败：{reason}"),
+            (CheckCodeError::DrawFailure(reason), Language::En) => format!("Failed to draw the check code: {reason}")
+        }
+    }
+}
+impl Display for CheckCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(Language::default())[..])
+    }
+}
+impl ::std::error::Error for CheckCodeError {}
+impl From<JsValue> for CheckCodeError {
+    fn from(error: JsValue) -> Self {
+        CheckCodeError::DrawFailure(error.as_string().unwrap_or_else(|| format!("{error:?}")))
+    }
+}
+impl From<OptionsError> for CheckCodeError {
+    fn from(error: OptionsError) -> Self {
+        CheckCodeError::InvalidOptions(error)
+    }
+}
@@ -0,0 +1,77 @@
+use ::web_sys::{HtmlInputElement, InputEvent};
+use ::yew::{AttrValue, Callback, classes, function_component, html, Html, NodeRef, Properties, TargetCast, use_memo, use_node_ref, use_state_eq};
+use crate::{CanvasCheckCode, CheckCode, CheckCodeController, i18n::Text, Props as CanvasProps};
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct FieldProps {
+    /// 表单提交时，文本输入框的字段名
+    #[prop_or(AttrValue::Static("checkcode"))]
+    pub name: AttrValue,
+    /// 缺省值是按`canvas.lang`本地化的提示文字
+    #[prop_or_default]
+    pub placeholder: Option<AttrValue>,
+    /// 透传给内部`<CanvasCheckCode>`的输入参数
+    #[prop_or_else(|| ::yew::props!(CanvasProps {}))]
+    pub canvas: CanvasProps,
+    #[prop_or_default]
+    pub on_validated: Callback<bool>
+}
+/// 捆绑了文本输入框、图形验证码与【换一张】按钮的表单字段。文本输入框是非受控的：每次输入都会被立即校对，
+/// 而每次生成新验证码都会清空它。
+#[function_component(CheckCodeField)]
+pub fn check_code_field(props: &FieldProps) -> Html {
+    let input_ref = use_node_ref();
+    let valid = use_state_eq(|| false);
+    let fallback = use_memo((), |_| CheckCodeController::new());
+    let controller = props.canvas.controller.clone().unwrap_or_else(|| (*fallback).clone());
+    let lang = props.canvas.lang;
+    let validate = {
+        let valid = valid.clone();
+        let on_validated = props.on_validated.clone();
+        move |result: bool| {
+            valid.set(result);
+            on_validated.emit(result);
+        }
+    };
+    let oninput = {
+        let controller = controller.clone();
+        let validate = validate.clone();
+        Callback::from(move |event: InputEvent| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            validate(controller.verify(&input.value()[..]));
+        })
+    };
+    let on_check_code_change = {
+        let input_ref = input_ref.clone();
+        let on_check_code_change = props.canvas.on_check_code_change.clone();
+        Callback::from(move |check_code: CheckCode| {
+            // 旧答案作废
+            if let CheckCode::Update(_) = check_code {
+                clear_input(&input_ref);
+                validate(false);
+            }
+            on_check_code_change.emit(check_code);
+        })
+    };
+    // 文本输入框同时参与浏览器原生的表单校验。【换一张】按钮由内部的`<CanvasCheckCode>`提供，以免非文字题型下出现两个。
+    let canvas = CanvasProps {
+        controller: Some(controller),
+        refresh_button: true,
+        on_check_code_change,
+        linked_input: props.canvas.linked_input.clone().or_else(|| Some(input_ref.clone())),
+        ..props.canvas.clone()
+    };
+    html! {
+        <span class={classes!("wasm-yew-canvas-checkcode-field", (*valid).then_some("valid"))}>
+            <input ref={input_ref} type="text" name={&props.name} autocomplete="off"
+                maxlength={props.canvas.check_code_len.to_string()}
+                placeholder={props.placeholder.clone().unwrap_or(AttrValue::Static(Text::Placeholder.localize(lang)))}
+                aria-invalid={(!*valid).to_string()} {oninput} />
+            <CanvasCheckCode ..canvas />
+        </span>
+    }
+}
+fn clear_input(input_ref: &NodeRef) {
+    if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+        input.set_value("");
+    }
+}
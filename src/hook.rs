@@ -0,0 +1,65 @@
+use ::yew::{function_component, hook, html, Html, use_memo, use_state_eq, UseStateHandle};
+use crate::{CanvasCheckCode, CheckCode, CheckCodeController, CheckCodeError, Props};
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckCodeState {
+    /// 尚未完成首次绘制
+    Pending,
+    Ready,
+    /// 最近一次绘制失败
+    Failed(CheckCodeError)
+}
+/// `use_check_code()`钩子的返回值。经由`Props::handle`交给`<CanvasCheckCode>`或`<CheckCodeCanvas>`之后，
+/// 父组件就能读取当前验证码、校对用户输入与刷新验证码。与`CheckCodeController`不同，其状态变化会令父组件重新渲染。
+#[derive(Clone, Debug)]
+pub struct UseCheckCodeHandle {
+    controller: CheckCodeController,
+    challenge: UseStateHandle<Option<String>>,
+    state: UseStateHandle<CheckCodeState>
+}
+impl PartialEq for UseCheckCodeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.controller == other.controller && *self.challenge == *other.challenge && *self.state == *other.state
+    }
+}
+impl UseCheckCodeHandle {
+    pub fn refresh(&self) {
+        self.controller.refresh();
+    }
+    pub fn current_challenge(&self) -> Option<String> {
+        (*self.challenge).clone()
+    }
+    pub fn verify(&self, input: &str) -> bool {
+        self.challenge.as_deref() == Some(input)
+    }
+    pub fn state(&self) -> CheckCodeState {
+        (*self.state).clone()
+    }
+    /// 更多的操作（比如，重置与调整干扰强度），见`CheckCodeController`
+    pub fn controller(&self) -> &CheckCodeController {
+        &self.controller
+    }
+    pub(crate) fn on_check_code(&self, check_code: &CheckCode) {
+        let (CheckCode::Initialize(value) | CheckCode::Update(value) | CheckCode::Redraw(value)) = check_code;
+        self.challenge.set(Some(value.clone()));
+        self.state.set(CheckCodeState::Ready);
+    }
+    pub(crate) fn on_error(&self, error: &CheckCodeError) {
+        self.state.set(CheckCodeState::Failed(error.clone()));
+    }
+}
+#[hook]
+pub fn use_check_code() -> UseCheckCodeHandle {
+    UseCheckCodeHandle {
+        controller: (*use_memo((), |_| CheckCodeController::new())).clone(),
+        challenge: use_state_eq(|| None),
+        state: use_state_eq(|| CheckCodeState::Pending)
+    }
+}
+/// 原样透传输入参数给`<CanvasCheckCode>`的薄包装。绘制与生命周期仍由结构体组件`<CanvasCheckCode>`完成，
+/// 所以两者的行为完全相同。
+#[function_component(CheckCodeCanvas)]
+pub fn check_code_canvas(props: &Props) -> Html {
+    html! {
+        <CanvasCheckCode ..props.clone() />
+    }
+}
@@ -0,0 +1,44 @@
+/// 界面文案与错误信息的语言
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    Zh,
+    En
+}
+// 控件自带的界面文案
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Text {
+    Placeholder,
+    Refresh,
+    WrongCheckCode,
+    CanvasLabel,
+    Regenerated,
+    PlayAudio,
+    ClickPrompt,
+    SliderPrompt,
+    RotatePrompt
+}
+impl Text {
+    pub(crate) fn localize(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (Text::Placeholder, Language::Zh) => "请输入验证码",
+            (Text::Placeholder, Language::En) => "Enter the code",
+            (Text::Refresh, Language::Zh) => "换一张",
+            (Text::Refresh, Language::En) => "Refresh",
+            (Text::WrongCheckCode, Language::Zh) => "验证码输入错误",
+            (Text::WrongCheckCode, Language::En) => "The check code is wrong",
+            (Text::CanvasLabel, Language::Zh) => "图形验证码。按回车键或空格键换一张",
+            (Text::CanvasLabel, Language::En) => "Verification image. Press Enter or Space for a new one",
+            (Text::Regenerated, Language::Zh) => "已生成新的验证图片",
+            (Text::Regenerated, Language::En) => "New verification image generated",
+            (Text::PlayAudio, Language::Zh) => "播放语音验证码",
+            (Text::PlayAudio, Language::En) => "Play audio challenge",
+            (Text::ClickPrompt, Language::Zh) => "请依次点击：",
+            (Text::ClickPrompt, Language::En) => "Click in order: ",
+            (Text::SliderPrompt, Language::Zh) => "拖动滑块，使拼图块与缺口对齐",
+            (Text::SliderPrompt, Language::En) => "Drag the slider to fit the piece into the gap",
+            (Text::RotatePrompt, Language::Zh) => "拖动滑块，把图中的字符转正",
+            (Text::RotatePrompt, Language::En) => "Drag the slider to turn the character upright"
+        }
+    }
+}
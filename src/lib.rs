@@ -1,76 +1,180 @@
+// 为构建器生成链式的 setter 方法
+macro_rules! setters {
+    ($($field: ident: $type: ty),+) => {$(
+        pub fn $field(mut self, $field: $type) -> Self {
+            self.$field = $field;
+            self
+        }
+    )+};
+}
 mod core;
+mod audio;
+mod challenge;
+mod controller;
+mod error;
+mod field;
+mod hook;
+mod i18n;
+mod trace;
+mod wav;
 
 #[cfg(debug_assertions)]
 use ::gloo::console;
-use ::gloo::utils;
+use ::gloo::{events::EventListener, timers::callback::Timeout, utils};
+use ::js_sys::Function;
 use ::rand::{Rng, rngs::OsRng};
-use ::wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
-use ::web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent};
-use ::yew::{Callback, Component, Context, html, html::Scope, Html, NodeRef, Properties};
-use core::CanvasOpts;
+use ::std::cell::{Cell, RefCell};
+use ::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use ::web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlElement, HtmlInputElement, KeyboardEvent, MouseEvent, PointerEvent, ResizeObserver};
+use ::yew::{AttrValue, Callback, Component, Context, html, Html, NodeRef, Properties, TargetCast};
+pub use challenge::{ChallengeAnswer, ChallengeMode, ChallengeResponse, TrajectoryPoint, verify_click_order, verify_rotation, verify_slider};
+pub use controller::CheckCodeController;
+pub use core::{CanvasOpts, CanvasOptsBuilder, Context2d, CssLength, Difficulty, NoiseShape, NoiseShapeKind, OptionsError, Point, RedrawStats, render, RenderTarget, Sizing};
+pub use error::CheckCodeError;
+pub use field::{CheckCodeField, FieldProps};
+pub use hook::{CheckCodeCanvas, CheckCodeState, use_check_code, UseCheckCodeHandle};
+use audio::{AudioPlayback, speakable};
+use challenge::Slide;
+use i18n::Text;
+pub use i18n::Language;
+pub use trace::{InteractionTrace, TraceRecorder};
+pub use wav::{SampleBank, WavError, WavOpts};
 
-#[derive(Debug, PartialEq, Properties)]
+#[derive(Clone, Debug, PartialEq, Properties)]
 pub struct Props {
     #[prop_or(150.0)]
     pub width: f64,
     #[prop_or(50.0)]
     pub height: f64,
+    #[prop_or_default]
+    pub sizing: Sizing,
     #[prop_or(7.0)]
     pub star_size: f64,
     #[prop_or(25)]
-    pub star_count: u8,
+    pub star_count: u32,
+    #[prop_or_else(|| vec![(NoiseShapeKind::Star, 1)])]
+    pub noise_shapes: Vec<(NoiseShapeKind, u8)>,
     #[prop_or(22.0)]
     pub font_size: f64,
+    #[prop_or(0)]
+    pub decoy_count: u32,
     #[prop_or(5)]
-    pub check_code_len: u8,
+    pub check_code_len: u16,
+    #[prop_or_default]
+    pub charset: AttrValue,
+    #[prop_or(150)]
+    pub resize_debounce: u32,
+    #[prop_or_default]
+    pub mode: ChallengeMode,
+    #[prop_or_default]
     pub on_check_code_change: Callback<CheckCode>,
+    #[prop_or_default]
+    pub on_check_code_image: Option<Callback<(CheckCode, String)>>,
+    #[prop_or((|_| {}).into())]
+    pub on_redraw: Callback<RedrawStats>,
     #[prop_or((|_| {}).into())]
-    pub reversed_hook: Callback<Scope<CanvasCheckCode>>,
+    pub on_error: Callback<CheckCodeError>,
+    #[prop_or((|_| {}).into())]
+    pub on_response: Callback<ChallengeResponse>,
+    #[prop_or_default]
+    pub lang: Language,
+    #[prop_or(false)]
+    pub refresh_button: bool,
+    #[prop_or(false)]
+    pub audio: bool,
+    #[prop_or(false)]
+    pub trace: bool,
+    #[prop_or_default]
+    pub aria_label: Option<AttrValue>,
+    #[prop_or_default]
+    pub aria_describedby: Option<AttrValue>,
+    #[prop_or_default]
+    pub controller: Option<CheckCodeController>,
+    #[prop_or_default]
+    pub handle: Option<UseCheckCodeHandle>,
+    #[prop_or_default]
+    pub hidden_input: Option<AttrValue>,
+    #[prop_or_default]
+    pub linked_input: Option<NodeRef>,
 }
 pub enum Message {
-    UpdateCheckCode
+    // 首次绘制完成。验证码与随机编号都是在 rendered() 里才生成的，须再渲染一次才能显示于隐藏字段与提示文字内。
+    Initialized,
+    UpdateCheckCode,
+    Redraw,
+    ShowError(CheckCodeError),
+    Reset,
+    SetDifficulty(Difficulty),
+    ExportImage(String, Option<f64>, Callback<String>),
+    // 图片的 MIME 类型与压缩质量，及`Promise`的 resolve 与 reject 函数
+    ExportBlob(String, Option<f64>, Function, Function),
+    ValidateLinkedInput,
+    PlayAudio,
+    SlideStart(Point, f64),
+    SlideMove(Point, f64),
+    SlideStep(f64, f64),
+    SlideEnd,
+    Pointer(PointerEvent),
+    ExportTrace(Callback<Option<InteractionTrace>>)
+}
+impl Props {
+    // 经由`controller`与`handle`输入参数交给控件的全部句柄
+    fn controllers(&self) -> impl Iterator<Item = &CheckCodeController> {
+        self.controller.iter().chain(self.handle.iter().map(UseCheckCodeHandle::controller))
+    }
+    // 空字符集代表采用内置的字符集。提供了语音验证码时，仅保留能按`lang`被读得清的字符。
+    fn charset(&self) -> Vec<char> {
+        let charset: Vec<char> = if self.charset.is_empty() {
+            CHARS.to_vec()
+        } else {
+            self.charset.chars().collect()
+        };
+        if !self.audio {
+            return charset;
+        }
+        charset.into_iter().filter(|char| speakable(*char, self.lang)).collect()
+    }
 }
+#[derive(Clone, Debug, PartialEq)]
 pub enum CheckCode {
     Initialize(String),
-    Update(String)
+    Update(String),
+    Redraw(String)
 }
 pub struct CanvasCheckCode {
     canvas_ref: NodeRef,
+    // 滑块拼图题型下，承载拼图块的画布与滑轨
+    piece_ref: NodeRef,
+    slider_ref: NodeRef,
     unique_id: String,
-}
-macro_rules! draw_canvas {
-    (@core $self: ident, $ctx: ident, $canvas: ident, $custom_canvas: block, $timing: ident) => {
-        let props = $ctx.props();
-        let check_code = gen_random_characters(props.check_code_len); //TODO: 如何将内部生成的检验码，输出到组件外;
-        let $canvas = $self.canvas_ref.cast::<HtmlCanvasElement>().ok_or("未能获取 canvas 元素")?;
-        $custom_canvas
-        let window = utils::window();
-        let canvas_opts = CanvasOpts::with_canvas(&window, &$canvas, props)?;
-        $canvas.set_attribute("width", &format!("{}px", canvas_opts.width)[..])?;
-        $canvas.set_attribute("height", &format!("{}px", canvas_opts.height)[..])?;
-        core::redraw(
-            $canvas.get_context("2d")?.ok_or("浏览器画布不支持 2D 渲染上下文")?.dyn_into::<CanvasRenderingContext2d>()?,
-            canvas_opts,
-            &check_code[..]
-        )?;
-        props.on_check_code_change.emit(CheckCode::$timing(check_code));
-    };
-    ($self: ident, $ctx: ident, $canvas: ident, $custom_canvas: block) => {
-        draw_canvas!(@core $self, $ctx, $canvas, $custom_canvas, Initialize);
-    };
-    ($self: ident, $ctx: ident) => {
-        draw_canvas!(@core $self, $ctx, canvas, {}, Update);
-    };
+    check_code: String,
+    challenge_id: String,
+    // 验证码被换新的次数。读屏软件仅播报内容有变化的`aria-live`区域，所以连续两次的播报文案须有所区别。
+    announcements: u32,
+    // 是否已执行过首次绘制。首次绘制可能失败（比如，非法的输入参数），所以不能以验证码是否为空来判断。
+    initialized: bool,
+    error: Option<CheckCodeError>,
+    // 点选模式下，各个字符的中心点与`UI`用户已点选的位置
+    click_targets: Vec<Point>,
+    clicks: Vec<Point>,
+    // 画布上尚未抬起的指针：其编号、按下的位置与时间戳
+    press: Option<(i32, Point, f64)>,
+    slide: Slide,
+    difficulty: Option<Difficulty>,
+    pixel_ratio_listener: Option<EventListener>,
+    resize_observer: Option<(ResizeObserver, Closure<dyn FnMut()>)>,
+    linked_input_listener: Option<EventListener>,
+    trace: Option<TraceRecorder>,
+    audio_playback: Option<AudioPlayback>
 }
 impl CanvasCheckCode {
-    fn init_canvas(&self, ctx: &Context<Self>) -> Result<(), JsValue> {
+    fn init_canvas(&mut self, ctx: &Context<Self>) -> Result<(), CheckCodeError> {
         let canvas_data_key = format!("data-{}", &self.unique_id[..]);
-        draw_canvas!(self, ctx, canvas, {
-            canvas.set_attribute(&canvas_data_key[..], "")?;
-            canvas.class_list().add_1("wasm-yew-canvas-checkcode")?;
-        });
+        let canvas = self.canvas_ref.cast::<HtmlCanvasElement>().ok_or(CheckCodeError::MissingCanvas)?;
+        canvas.set_attribute(&canvas_data_key[..], "")?;
+        canvas.class_list().add_1("wasm-yew-canvas-checkcode")?;
         let document = utils::document();
-        let head = document.head().ok_or("未运行于浏览器环境内，没有 head DOM 结点")?;
+        let head = document.head().ok_or(CheckCodeError::NoHeadElement)?;
         // 添加样式
         let style_data_key = format!("data-wasm-yew-canvas-checkcode-{}", &self.unique_id[..]);
         let _style = head.query_selector(&format!("style[{}]", &style_data_key[..])[..])?.ok_or("没有现成的样式dom元素").or_else(|_| {
@@ -80,66 +184,590 @@ impl CanvasCheckCode {
             style.set_text_content(Some(&format!(include_str!("./wasm_yew_canvas_checkcode.css"), &canvas_data_key[..])[..]));
             Ok::<_, JsValue>(style)
         })?;
+        self.watch_pixel_ratio(ctx);
+        self.watch_resize(ctx, &canvas)?;
+        self.watch_linked_input(ctx);
+        self.check_code = gen_check_code(ctx.props());
+        self.challenge_id = gen_challenge_id();
+        self.draw_canvas(ctx)?;
+        self.emit_check_code(ctx, CheckCode::Initialize(self.check_code.clone()));
+        Ok(())
+    }
+    fn update_canvas(&mut self, ctx: &Context<Self>) -> Result<(), CheckCodeError> {
+        // 正在朗读的是上一个验证码
+        self.stop_audio();
+        self.check_code = gen_check_code(ctx.props());
+        self.challenge_id = gen_challenge_id();
+        if let Some(trace) = &mut self.trace {
+            trace.restart(now());
+        }
+        self.draw_canvas(ctx)?;
+        self.announcements += 1;
+        self.emit_check_code(ctx, CheckCode::Update(self.check_code.clone()));
+        Ok(())
+    }
+    fn draw_canvas(&mut self, ctx: &Context<Self>) -> Result<(), CheckCodeError> {
+        let props = ctx.props();
+        let canvas = self.canvas_ref.cast::<HtmlCanvasElement>().ok_or(CheckCodeError::MissingCanvas)?;
+        let window = utils::window();
+        let mut canvas_opts = CanvasOpts::with_canvas(&window, &canvas, props)?;
+        if let Some(difficulty) = self.difficulty {
+            difficulty.apply(&mut canvas_opts);
+        }
+        // 画布的像素缓冲区按 devicePixelRatio 放大，而其 CSS 尺寸保持不变，以免在高分屏上发虚。
+        let pixel_ratio = window.device_pixel_ratio();
+        canvas.set_width((canvas_opts.width * pixel_ratio).round() as u32);
+        canvas.set_height((canvas_opts.height * pixel_ratio).round() as u32);
+        let style = canvas.style();
+        style.set_property("width", &format!("{}px", canvas_opts.width)[..])?;
+        style.set_property("height", &format!("{}px", canvas_opts.height)[..])?;
+        let context = get_context(&canvas)?;
+        context.set_transform(pixel_ratio, 0_f64, 0_f64, pixel_ratio, 0_f64, 0_f64)?;
+        // 拼图块画布与主画布同尺寸，且以绝对定位叠放于其上
+        let piece_context = match self.piece_ref.cast::<HtmlCanvasElement>() {
+            Some(piece) if props.mode.has_slider() => {
+                piece.set_width(canvas.width());
+                piece.set_height(canvas.height());
+                let style = piece.style();
+                style.set_property("width", &format!("{}px", canvas_opts.width)[..])?;
+                style.set_property("height", &format!("{}px", canvas_opts.height)[..])?;
+                style.set_property("position", "absolute")?;
+                style.set_property("pointer-events", "none")?;
+                let piece_context = get_context(&piece)?;
+                piece_context.set_transform(pixel_ratio, 0_f64, 0_f64, pixel_ratio, 0_f64, 0_f64)?;
+                Some(piece_context)
+            },
+            _ => None
+        };
+        let canvas_width = canvas_opts.width;
+        let stats = core::redraw(&context, piece_context.as_ref().map(|context| context as &dyn Context2d), canvas_opts, &self.check_code[..])?;
+        // 字符的位置变了，之前的点选作废
+        self.clicks.clear();
+        self.click_targets = stats.glyph_centers.clone();
+        // 缺口的位置（或字符的旋转角度）也变了，拼图块回到起点
+        if stats.slider_target.is_some() || stats.rotation_target.is_some() {
+            self.slide = Slide::new(canvas_width - SLIDER_HANDLE_SIZE);
+            if let Some(slider) = self.slider_ref.cast::<HtmlElement>() {
+                slider.style().set_property("width", &format!("{canvas_width}px")[..])?;
+            }
+            self.apply_slide_offset(props.mode)?;
+        }
+        props.controllers().for_each(|controller| controller.on_redraw(&self.click_targets[..], stats.slider_target, stats.rotation_target));
+        props.on_redraw.emit(stats);
+        Ok(())
+    }
+    // 窗口在不同分辨率的显示器之间被拖动时，devicePixelRatio 会变化。仅匹配当前比率的媒体查询能感知这种变化。
+    fn watch_pixel_ratio(&mut self, ctx: &Context<Self>) {
+        let window = utils::window();
+        let query = format!("(resolution: {}dppx)", window.device_pixel_ratio());
+        self.pixel_ratio_listener = window.match_media(&query[..]).ok().flatten().map(|media_query_list| {
+            let scope = ctx.link().clone();
+            EventListener::new(&media_query_list, "change", move |_| scope.send_message(Message::Redraw))
+        });
+    }
+    // 容器尺寸变化（比如，百分比宽度的画布随窗口缩放）之后，以同一个验证码、按新尺寸重绘。
+    // 画布自身的 CSS 尺寸被钉在了行内样式上，所以还需观察其父元素。
+    fn watch_resize(&mut self, ctx: &Context<Self>, canvas: &HtmlCanvasElement) -> Result<(), CheckCodeError> {
+        let scope = ctx.link().clone();
+        let debounce = ctx.props().resize_debounce;
+        let timeout = RefCell::new(None::<Timeout>);
+        // 开始观察时，ResizeObserver 会立即回调一次。此时画布才刚被绘制，无需重绘。
+        let observed = Cell::new(false);
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            if !observed.replace(true) {
+                return;
+            }
+            let scope = scope.clone();
+            timeout.replace(Some(Timeout::new(debounce, move || scope.send_message(Message::Redraw))));
+        });
+        let observer = ResizeObserver::new(callback.as_ref().unchecked_ref())?;
+        observer.observe(canvas);
+        if let Some(parent) = canvas.parent_element() {
+            observer.observe(&parent);
+        }
+        self.resize_observer = Some((observer, callback));
+        Ok(())
+    }
+    // 关联文本输入框的每次输入都被校对，以便浏览器原生的表单校验（比如，`form.checkValidity()`）拦截错误的验证码
+    fn watch_linked_input(&mut self, ctx: &Context<Self>) {
+        self.linked_input_listener = ctx.props().linked_input.as_ref().and_then(NodeRef::cast::<HtmlInputElement>).map(|input| {
+            let scope = ctx.link().clone();
+            EventListener::new(&input, "input", move |_| scope.send_message(Message::ValidateLinkedInput))
+        });
+    }
+    fn validate_linked_input(&self, ctx: &Context<Self>) {
+        let props = ctx.props();
+        if let Some(input) = props.linked_input.as_ref().and_then(NodeRef::cast::<HtmlInputElement>) {
+            let value = input.value();
+            // 空值留给`required`属性校验
+            if value.is_empty() || value == self.check_code {
+                input.set_custom_validity("");
+            } else {
+                input.set_custom_validity(Text::WrongCheckCode.localize(props.lang));
+            }
+        }
+    }
+    fn refresh_canvas(&mut self, ctx: &Context<Self>) {
+        match self.update_canvas(ctx) {
+            Ok(_) => self.error = None,
+            Err(error) => self.fail(ctx, error)
+        }
+    }
+    fn unwatch_resize(&mut self) {
+        if let Some((observer, _callback)) = self.resize_observer.take() {
+            observer.disconnect();
+        }
+    }
+    fn redraw_canvas(&mut self, ctx: &Context<Self>) {
+        // 非文字题型的答案（字符、缺口的位置或字符的旋转角度）随重绘而变，所以重绘即是换一张
+        if ctx.props().mode != ChallengeMode::Text {
+            self.refresh_canvas(ctx);
+            return;
+        }
+        match self.draw_canvas(ctx) {
+            Ok(_) => {
+                self.error = None;
+                self.emit_check_code(ctx, CheckCode::Redraw(self.check_code.clone()));
+            },
+            Err(error) => self.fail(ctx, error)
+        }
+    }
+    fn emit_check_code(&self, ctx: &Context<Self>, check_code: CheckCode) {
+        let props = ctx.props();
+        props.controllers().for_each(|controller| controller.on_check_code(&check_code, &self.challenge_id[..]));
+        if let Some(handle) = &props.handle {
+            handle.on_check_code(&check_code);
+        }
+        // 编码图片颇费时间，所以仅在父控件提供了回调函数时才导出
+        if let (Some(callback), Some(canvas)) = (&props.on_check_code_image, self.canvas_ref.cast::<HtmlCanvasElement>()) {
+            if let Ok(data_url) = canvas.to_data_url() {
+                callback.emit((check_code.clone(), data_url));
+            }
+        }
+        props.on_check_code_change.emit(check_code);
+        // 父控件可能在回调函数内清空了关联的文本输入框
+        self.validate_linked_input(ctx);
+    }
+    fn export_image(&self, mime: &str, quality: Option<f64>, callback: Callback<String>) -> Result<(), CheckCodeError> {
+        let canvas = self.canvas_ref.cast::<HtmlCanvasElement>().ok_or(CheckCodeError::MissingCanvas)?;
+        callback.emit(canvas.to_data_url_with_type_and_encoder_options(mime, &quality.map(JsValue::from).unwrap_or(JsValue::UNDEFINED))?);
         Ok(())
     }
-    fn update_canvas(&self, ctx: &Context<Self>) -> Result<(), JsValue> {
-        draw_canvas!(self, ctx);
+    // 浏览器异步地编码图片，再以`Blob`（画布为空时是`null`）调用 resolve 函数
+    fn export_blob(&self, mime: &str, quality: Option<f64>, resolve: &Function) -> Result<(), CheckCodeError> {
+        let canvas = self.canvas_ref.cast::<HtmlCanvasElement>().ok_or(CheckCodeError::MissingCanvas)?;
+        canvas.to_blob_with_type_and_encoder_options(resolve, mime, &quality.map(JsValue::from).unwrap_or(JsValue::UNDEFINED))?;
         Ok(())
     }
+    fn click(&mut self, ctx: &Context<Self>, point: Point) -> Result<(), CheckCodeError> {
+        // 作答完毕之后，须先换一张
+        if self.clicks.len() >= self.click_targets.len() {
+            return Ok(());
+        }
+        let canvas = self.canvas_ref.cast::<HtmlCanvasElement>().ok_or(CheckCodeError::MissingCanvas)?;
+        self.clicks.push(point);
+        core::draw_click_mark(&get_context(&canvas)?, &point, self.clicks.len())?;
+        if self.clicks.len() == self.click_targets.len() {
+            ctx.props().on_response.emit(ChallengeResponse {
+                challenge_id: self.challenge_id.clone(),
+                answer: ChallengeAnswer::ClickOrder(self.clicks.clone()),
+                trace: self.trace_snapshot()
+            });
+        }
+        Ok(())
+    }
+    // 拖动中的重绘过于频繁，所以直接改写拼图块画布与滑块的行内样式，而不经由`view()`
+    fn apply_slide_offset(&self, mode: ChallengeMode) -> Result<(), CheckCodeError> {
+        let translate = format!("translateX({}px)", self.slide.offset);
+        // 转正题型的拼图块画布绕其中心旋转，而不是平移
+        let (piece_transform, value, max) = match mode {
+            ChallengeMode::Rotate => (format!("rotate({}deg)", self.slide.angle()), self.slide.angle(), 360_f64),
+            _ => (translate.clone(), self.slide.offset, self.slide.max())
+        };
+        if let Some(piece) = self.piece_ref.cast::<HtmlElement>() {
+            piece.style().set_property("transform", &piece_transform[..])?;
+        }
+        if let Some(handle) = self.slider_ref.cast::<Element>().and_then(|slider| slider.first_element_child()).and_then(|handle| handle.dyn_into::<HtmlElement>().ok()) {
+            handle.style().set_property("transform", &translate[..])?;
+            // 滑轨宽度取决于画布的实际尺寸，所以取值范围与当前值一样，都在绘制之后才被写入
+            handle.set_attribute("aria-valuemax", &format!("{}", max.round())[..])?;
+            handle.set_attribute("aria-valuenow", &format!("{}", value.round())[..])?;
+        }
+        Ok(())
+    }
+    fn slide(&mut self, ctx: &Context<Self>, msg: Message) -> Result<(), CheckCodeError> {
+        match msg {
+            Message::SlideStart(pointer, time) => {
+                self.slide.start(pointer, time);
+                if let Some(trace) = &mut self.trace {
+                    trace.interact(time);
+                }
+            },
+            Message::SlideMove(pointer, time) => if self.slide.move_to(pointer, time) {
+                self.apply_slide_offset(ctx.props().mode)?;
+            },
+            Message::SlideStep(delta, time) => if self.slide.step(delta, time) {
+                if let Some(trace) = &mut self.trace {
+                    trace.interact(time);
+                }
+                self.apply_slide_offset(ctx.props().mode)?;
+            },
+            _ => if let Some(ChallengeAnswer::Slider {offset, trajectory}) = self.slide.finish() {
+                let answer = match ctx.props().mode {
+                    ChallengeMode::Rotate => ChallengeAnswer::Rotate {angle: self.slide.angle(), trajectory},
+                    _ => ChallengeAnswer::Slider {offset, trajectory}
+                };
+                ctx.props().on_response.emit(ChallengeResponse {
+                    challenge_id: self.challenge_id.clone(),
+                    answer,
+                    trace: self.trace_snapshot()
+                });
+            }
+        }
+        Ok(())
+    }
+    // 由指针的按下与抬起合成轻触，而不依赖 click 事件。在触屏上，click 事件会被延迟、被双击缩放吞掉，或在滚动之后误触发。
+    fn pointer(&mut self, ctx: &Context<Self>, event: PointerEvent) -> bool {
+        self.trace_pointer(&event);
+        let point = Point(event.offset_x() as f64, event.offset_y() as f64);
+        let time = event.time_stamp();
+        match &event.type_()[..] {
+            "pointerdown" if event.is_primary() && event.button() == 0 => self.press = Some((event.pointer_id(), point, time)),
+            "pointerup" => {
+                let Some((pointer_id, origin, since)) = self.press.take() else {
+                    return false;
+                };
+                // 长按与拖动（比如，滚动页面）都不算轻触
+                if pointer_id != event.pointer_id() || time - since >= LONG_PRESS || (point.0 - origin.0).hypot(point.1 - origin.1) > TAP_SLOP {
+                    return false;
+                }
+                match ctx.props().mode {
+                    ChallengeMode::ClickOrder => if let Err(error) = self.click(ctx, point) {
+                        self.fail(ctx, error);
+                        return true;
+                    },
+                    ChallengeMode::Text => {
+                        #[cfg(debug_assertions)]
+                        console::info!("刷新验证码");
+                        self.refresh_canvas(ctx);
+                        return true;
+                    },
+                    // 拼图块随画布一起被换掉会令拖动半途而废，所以只能经由【换一张】按钮刷新
+                    ChallengeMode::Slider | ChallengeMode::Rotate => ()
+                }
+            },
+            "pointercancel" => self.press = None,
+            _ => ()
+        }
+        false
+    }
+    fn trace_pointer(&mut self, event: &PointerEvent) {
+        let Some(trace) = &mut self.trace else {
+            return;
+        };
+        let time = event.time_stamp();
+        match &event.type_()[..] {
+            "pointermove" => trace.pointer_move(Point(event.offset_x() as f64, event.offset_y() as f64), time),
+            "pointerenter" => trace.pointer_enter(time),
+            "pointerleave" => trace.pointer_leave(time),
+            "pointerdown" => trace.interact(time),
+            _ => ()
+        }
+    }
+    fn trace_snapshot(&self) -> Option<InteractionTrace> {
+        self.trace.as_ref().map(|trace| trace.snapshot(now()))
+    }
+    fn stop_audio(&mut self) {
+        if let Some(playback) = self.audio_playback.take() {
+            playback.stop();
+        }
+    }
+    fn fail(&mut self, ctx: &Context<Self>, error: CheckCodeError) {
+        #[cfg(debug_assertions)]
+        console::error!("图形验证码出错", error.to_string());
+        if let Some(handle) = &ctx.props().handle {
+            handle.on_error(&error);
+        }
+        ctx.props().on_error.emit(error.clone());
+        self.error = Some(error);
+    }
 }
 impl Component for CanvasCheckCode {
     type Message = Message;
     type Properties = Props;
     fn create(ctx: &Context<Self>) -> Self {
         let props = ctx.props();
-        let scope = ctx.link();
-        props.reversed_hook.emit(scope.clone());
+        props.controllers().for_each(|controller| controller.attach(ctx.link().clone()));
         Self {
             canvas_ref: NodeRef::default(),
-            unique_id: gen_random_characters(16)
+            piece_ref: NodeRef::default(),
+            slider_ref: NodeRef::default(),
+            unique_id: gen_random_characters(16, &CHARS),
+            check_code: String::new(),
+            challenge_id: String::new(),
+            announcements: 0,
+            initialized: false,
+            error: None,
+            click_targets: Vec::new(),
+            clicks: Vec::new(),
+            press: None,
+            slide: Slide::default(),
+            difficulty: None,
+            pixel_ratio_listener: None,
+            resize_observer: None,
+            linked_input_listener: None,
+            trace: props.trace.then(|| TraceRecorder::new(now())),
+            audio_playback: None
         }
     }
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Message::UpdateCheckCode => self.update_canvas(ctx).unwrap_throw()
+            Message::UpdateCheckCode => self.refresh_canvas(ctx),
+            Message::Redraw => {
+                self.watch_pixel_ratio(ctx);
+                self.redraw_canvas(ctx);
+            },
+            Message::Initialized => (),
+            Message::ShowError(error) => self.fail(ctx, error),
+            Message::Reset => {
+                self.difficulty = None;
+                self.refresh_canvas(ctx);
+            },
+            Message::SetDifficulty(difficulty) => {
+                self.difficulty = Some(difficulty);
+                self.refresh_canvas(ctx);
+            },
+            Message::ExportImage(mime, quality, callback) => {
+                if let Err(error) = self.export_image(&mime[..], quality, callback) {
+                    self.fail(ctx, error);
+                }
+                return false;
+            },
+            // 导出失败只是拒绝了父控件的`Promise`，而不必在画布旁显示错误
+            Message::ExportBlob(mime, quality, resolve, reject) => {
+                if let Err(error) = self.export_blob(&mime[..], quality, &resolve) {
+                    let _ = reject.call1(&JsValue::NULL, &JsValue::from(error.to_string()));
+                }
+                return false;
+            },
+            Message::ValidateLinkedInput => {
+                self.validate_linked_input(ctx);
+                return false;
+            },
+            Message::Pointer(event) => return self.pointer(ctx, event),
+            Message::ExportTrace(callback) => {
+                callback.emit(self.trace_snapshot());
+                return false;
+            },
+            msg @ (Message::SlideStart(..) | Message::SlideMove(..) | Message::SlideStep(..) | Message::SlideEnd) => {
+                if let Err(error) = self.slide(ctx, msg) {
+                    self.fail(ctx, error);
+                    return true;
+                }
+                return false;
+            },
+            Message::PlayAudio => {
+                self.stop_audio();
+                match AudioPlayback::play(&self.check_code[..], ctx.props().lang) {
+                    Ok(playback) => self.audio_playback = Some(playback),
+                    Err(error) => self.fail(ctx, error)
+                }
+            }
+        }
+        true
+    }
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        let props = ctx.props();
+        macro_rules! differ {
+            ($($field: ident),+) => {$(props.$field != old_props.$field)||+};
+        }
+        if differ!(trace) {
+            self.trace = props.trace.then(|| TraceRecorder::new(now()));
+        }
+        if differ!(controller, handle) {
+            old_props.controllers().filter(|old| !props.controllers().any(|new| new == *old)).for_each(CheckCodeController::detach);
+            props.controllers().for_each(|controller| controller.attach(ctx.link().clone()));
+        }
+        // 尚未执行首次绘制时，rendered() 生命周期函数会负责绘制
+        if !self.initialized {
+            return true;
+        }
+        if differ!(linked_input) {
+            self.watch_linked_input(ctx);
+            self.validate_linked_input(ctx);
+        }
+        if differ!(resize_debounce) {
+            if let Some(canvas) = self.canvas_ref.cast::<HtmlCanvasElement>() {
+                self.unwatch_resize();
+                if let Err(error) = self.watch_resize(ctx, &canvas) {
+                    self.fail(ctx, error);
+                }
+            }
+        }
+        if differ!(check_code_len, mode) || props.charset() != old_props.charset() {
+            // 验证码的答案变了（换了题型也算，比如点选模式的验证码字符互不重复），须重新生成
+            self.refresh_canvas(ctx);
+        } else if differ!(width, height, sizing, star_size, star_count, noise_shapes, font_size, decoy_count) {
+            // 仅是外观变了，以同一个验证码重绘
+            self.redraw_canvas(ctx);
         }
         true
     }
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
         let scope = ctx.link();
-        html! {
-            <canvas ref={self.canvas_ref.clone()} onclick={
-                scope.callback(move |event: MouseEvent| {
+        let mode = props.mode;
+        // 无障碍树内仅有画布的用途说明，而绝不包含验证码的答案
+        let aria_label = props.aria_label.clone().unwrap_or(AttrValue::Static(Text::CanvasLabel.localize(props.lang)));
+        let announcement = match self.announcements {
+            0 => String::new(),
+            count => format!("{}{}", Text::Regenerated.localize(props.lang), if count % 2 == 0 {"\u{a0}"} else {""})
+        };
+        let pointer = scope.callback(Message::Pointer);
+        // 仅在开启了交互痕迹记录时才监听指针的移动
+        let trace = props.trace.then(|| pointer.clone());
+        let prompt = if mode == ChallengeMode::Rotate {Text::RotatePrompt} else {Text::SliderPrompt};
+        html! {<>
+            // 拼图块画布被绝对定位在其静态位置上，恰好与紧随其后的主画布重叠
+            <canvas ref={self.piece_ref.clone()} class="wasm-yew-canvas-checkcode-piece" aria-hidden="true" hidden={!mode.has_slider()} />
+            <canvas ref={self.canvas_ref.clone()} tabindex="0" role="button" aria-label={aria_label} aria-describedby={props.aria_describedby.clone()} onclick={
+                // 轻触已被 pointerup 处理，合成的 click 事件仅被拦截，以免冒泡至父控件
+                Callback::from(|event: MouseEvent| {
                     event.prevent_default();
                     event.stop_propagation();
-                    event.stop_immediate_propagation();
-                    #[cfg(debug_assertions)]
-                    console::info!("刷新验证码");
-                    Message::UpdateCheckCode
+                })
+            } oncontextmenu={
+                // 屏蔽触屏长按弹出的【保存图片】菜单
+                Callback::from(|event: MouseEvent| event.prevent_default())
+            } onpointerdown={pointer.clone()} onpointerup={pointer.clone()} onpointercancel={pointer} onpointermove={trace.clone()} onpointerenter={trace.clone()} onpointerleave={trace} onkeydown={
+                // 与原生按钮一致，回车键与空格键都会刷新验证码
+                scope.batch_callback(move |event: KeyboardEvent| {
+                    if event.repeat() || !matches!(&event.key()[..], "Enter" | " ") {
+                        return None;
+                    }
+                    event.prevent_default();
+                    Some(Message::UpdateCheckCode)
                 })
             } />
-        }
+            if let Some(error) = &self.error {
+                <span class="wasm-yew-canvas-checkcode-error" role="alert">{error.localize(props.lang)}</span>
+            }
+            // 点选模式下，点击画布不再刷新验证码，所以总是提供【换一张】按钮
+            if props.refresh_button || mode != ChallengeMode::Text {
+                <button type="button" class="wasm-yew-canvas-checkcode-refresh" onclick={
+                    scope.callback(|_: MouseEvent| Message::UpdateCheckCode)
+                }>{Text::Refresh.localize(props.lang)}</button>
+            }
+            if props.audio {
+                <button type="button" class="wasm-yew-canvas-checkcode-audio" onclick={
+                    scope.callback(|_: MouseEvent| Message::PlayAudio)
+                }>{Text::PlayAudio.localize(props.lang)}</button>
+            }
+            if mode == ChallengeMode::ClickOrder {
+                <span class="wasm-yew-canvas-checkcode-prompt">{Text::ClickPrompt.localize(props.lang)}{self.check_code.chars().map(String::from).collect::<Vec<_>>().join(" ")}</span>
+            }
+            if mode.has_slider() {
+                <span ref={self.slider_ref.clone()} class="wasm-yew-canvas-checkcode-slider" style={format!("width: {}px", self.slide.max() + SLIDER_HANDLE_SIZE)}>
+                    <span tabindex="0" role="slider" aria-label={prompt.localize(props.lang)} aria-valuemin="0" onpointerdown={
+                        scope.callback(|event: PointerEvent| {
+                            event.prevent_default();
+                            // 捕获指针之后，即便指针滑出了滑块，拖动也不会中断
+                            let _ = event.target_unchecked_into::<Element>().set_pointer_capture(event.pointer_id());
+                            Message::SlideStart(Point(event.client_x() as f64, event.client_y() as f64), event.time_stamp())
+                        })
+                    } onpointermove={
+                        scope.callback(|event: PointerEvent| Message::SlideMove(Point(event.client_x() as f64, event.client_y() as f64), event.time_stamp()))
+                    } onpointerup={
+                        scope.callback(|_: PointerEvent| Message::SlideEnd)
+                    } onpointercancel={
+                        scope.callback(|_: PointerEvent| Message::SlideEnd)
+                    } onkeydown={
+                        scope.batch_callback(|event: KeyboardEvent| {
+                            let msg = match &event.key()[..] {
+                                "ArrowLeft" => Message::SlideStep(-2_f64, event.time_stamp()),
+                                "ArrowRight" => Message::SlideStep(2_f64, event.time_stamp()),
+                                "Enter" => Message::SlideEnd,
+                                _ => return None
+                            };
+                            event.prevent_default();
+                            Some(msg)
+                        })
+                    }>{"→"}</span>
+                </span>
+                <span class="wasm-yew-canvas-checkcode-prompt">{prompt.localize(props.lang)}</span>
+            }
+            <span class="wasm-yew-canvas-checkcode-live" aria-live="polite">{announcement}</span>
+            if let Some(name) = &props.hidden_input {
+                <input type="hidden" name={name} value={self.challenge_id.clone()} />
+            }
+        </>}
     }
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         if !first_render {
+            // 换成滑块题型时，滑块是在重绘之后才被挂载的，所以须在此补写其位置与 ARIA 属性
+            if ctx.props().mode.has_slider() {
+                if let Err(error) = self.apply_slide_offset(ctx.props().mode) {
+                    ctx.link().send_message(Message::ShowError(error));
+                }
+            }
             return;
         }
-        self.init_canvas(ctx).unwrap_throw();
+        self.initialized = true;
+        match self.init_canvas(ctx) {
+            Ok(_) => ctx.link().send_message(Message::Initialized),
+            Err(error) => ctx.link().send_message(Message::ShowError(error))
+        }
+    }
+    fn destroy(&mut self, ctx: &Context<Self>) {
+        ctx.props().controllers().for_each(CheckCodeController::detach);
+        self.stop_audio();
+        self.unwatch_resize();
     }
 }
-fn gen_random_characters(count: u8) -> String {
-    const CHARS: [char; 67] = [
-        '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
-        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j',
-        'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't',
-        'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D',
-        'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N',
-        'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
-        'Y', 'Z', '你', '我', '他', '她', '它'
-    ];
+// 轻触的最长按压时长（毫秒）与最大位移（`CSS`像素）。超出即视为长按或拖动。
+const LONG_PRESS: f64 = 500_f64;
+const TAP_SLOP: f64 = 10_f64;
+// 滑块的边长（单位是`CSS`像素），与样式表保持一致
+const SLIDER_HANDLE_SIZE: f64 = 32_f64;
+const CHARS: [char; 67] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j',
+    'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't',
+    'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D',
+    'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N',
+    'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
+    'Y', 'Z', '你', '我', '他', '她', '它'
+];
+fn gen_random_characters(count: u16, charset: &[char]) -> String {
     let mut characters = "".to_string();
     for _ in 0..count {
-        characters.push(CHARS[OsRng.gen_range(0..CHARS.len())]);
+        characters.push(charset[OsRng.gen_range(0..charset.len())]);
     }
     characters
+}
+// 点选模式按字符作答，所以从去重后的字符集中不放回地抽取字符。超长的验证码会被`CanvasOptsBuilder`拒绝。
+fn gen_check_code(props: &Props) -> String {
+    let mut distinct = props.charset();
+    if props.mode != ChallengeMode::ClickOrder {
+        return gen_random_characters(props.check_code_len, &distinct[..]);
+    }
+    distinct.sort_unstable();
+    distinct.dedup();
+    let len = distinct.len();
+    let count = usize::from(props.check_code_len).min(len);
+    for index in 0..count {
+        distinct.swap(index, OsRng.gen_range(index..len));
+    }
+    distinct[..count].iter().collect()
+}
+// 验证码的随机编号仅由数字与英文字母组成
+fn gen_challenge_id() -> String {
+    gen_random_characters(16, &CHARS[..62])
+}
+// 与`Event::time_stamp()`同基准的当前时间戳
+fn now() -> f64 {
+    utils::window().performance().map(|performance| performance.now()).unwrap_or_default()
+}
+fn get_context(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, CheckCodeError> {
+    canvas.get_context("2d")?.ok_or(CheckCodeError::No2dContext)?.dyn_into::<CanvasRenderingContext2d>().map_err(|_| CheckCodeError::No2dContext)
 }
\ No newline at end of file
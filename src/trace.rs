@@ -0,0 +1,75 @@
+use crate::Point;
+// 记录条数的上限，以免回传给后端的数据无限膨胀
+const MAX_MOVES: usize = 256;
+const MAX_HOVERS: usize = 32;
+const MAX_REFRESHES: usize = 32;
+// 指针移动的最短采样间隔（毫秒）
+const MOVE_INTERVAL: f64 = 50_f64;
+/// `UI`用户与当前验证码的交互痕迹，以供后端甄别机器人。时间的单位都是毫秒。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InteractionTrace {
+    /// 指针在画布上的移动轨迹：每`50`毫秒至多采样一次的位置，及其自验证码出现以来的毫秒数
+    pub pointer_moves: Vec<(Point, f64)>,
+    /// 指针每次停留于画布之上的时长，最多`32`次
+    pub hover_durations: Vec<f64>,
+    /// 自验证码出现至首次按下指针（或按键）的时长
+    pub first_interaction: Option<f64>,
+    /// 自控件挂载以来，相邻两次换一张的时间间隔
+    pub refresh_intervals: Vec<f64>
+}
+/// 逐一记录交互事件的记录器。`trace = true`的控件内置了一个；在控件之外（比如，经由`render()`）绘制验证码的应用，
+/// 也可自行记录。事件的时间戳都应取自`Event::time_stamp()`或`performance.now()`。
+#[derive(Debug)]
+pub struct TraceRecorder {
+    started_at: f64,
+    hover_since: Option<f64>,
+    trace: InteractionTrace
+}
+impl TraceRecorder {
+    /// `now`是验证码出现的时刻
+    pub fn new(now: f64) -> Self {
+        TraceRecorder {
+            started_at: now,
+            hover_since: None,
+            trace: InteractionTrace::default()
+        }
+    }
+    /// 换一张：仅刷新节奏跨验证码保留，其它痕迹都属于上一个验证码
+    pub fn restart(&mut self, now: f64) {
+        let mut refresh_intervals = std::mem::take(&mut self.trace.refresh_intervals);
+        if refresh_intervals.len() < MAX_REFRESHES {
+            refresh_intervals.push(now - self.started_at);
+        }
+        self.started_at = now;
+        self.hover_since = self.hover_since.map(|_| now);
+        self.trace = InteractionTrace {refresh_intervals, ..Default::default()};
+    }
+    pub fn pointer_move(&mut self, point: Point, time: f64) {
+        let elapsed = time - self.started_at;
+        let moves = &mut self.trace.pointer_moves;
+        if moves.len() >= MAX_MOVES || moves.last().is_some_and(|(_, last)| elapsed - last < MOVE_INTERVAL) {
+            return;
+        }
+        moves.push((point, elapsed));
+    }
+    pub fn pointer_enter(&mut self, time: f64) {
+        self.hover_since = Some(time);
+    }
+    pub fn pointer_leave(&mut self, time: f64) {
+        let hovers = &mut self.trace.hover_durations;
+        if let Some(since) = self.hover_since.take().filter(|_| hovers.len() < MAX_HOVERS) {
+            hovers.push(time - since);
+        }
+    }
+    pub fn interact(&mut self, time: f64) {
+        self.trace.first_interaction.get_or_insert(time - self.started_at);
+    }
+    /// 截至`now`的交互痕迹。指针仍停留于画布之上时，其停留时长也被计入。
+    pub fn snapshot(&self, now: f64) -> InteractionTrace {
+        let mut trace = self.trace.clone();
+        if let Some(since) = self.hover_since.filter(|_| trace.hover_durations.len() < MAX_HOVERS) {
+            trace.hover_durations.push(now - since);
+        }
+        trace
+    }
+}
@@ -0,0 +1,187 @@
+use ::rand::Rng;
+use ::std::{collections::HashMap, f64::consts::PI, fmt::Display};
+use crate::Language;
+/// 预先录制的单字符读音。采样值是单声道`16`位`PCM`。
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleBank {
+    sample_rate: u32,
+    samples: HashMap<char, Vec<i16>>
+}
+impl SampleBank {
+    pub fn new(sample_rate: u32) -> Self {
+        SampleBank {
+            sample_rate: sample_rate.max(1),
+            samples: HashMap::new()
+        }
+    }
+    pub fn insert(mut self, char: char, samples: Vec<i16>) -> Self {
+        self.samples.insert(char, samples);
+        self
+    }
+}
+/// 脱离浏览器、以纯`Rust`演算语音验证码的`WAV`文件。样本库里有读音的字符被拼接其录音，数字则被合成为
+/// 电话拨号音`DTMF`。每个字符都带有随机的音高与语速抖动，整段音频还被混入了白噪声。
+#[derive(Clone, Debug, PartialEq)]
+pub struct WavOpts {
+    sample_rate: u32,
+    char_duration: f64,
+    gap_duration: f64,
+    pitch_jitter: f64,
+    tempo_jitter: f64,
+    noise_level: f64,
+    sample_bank: Option<SampleBank>
+}
+impl Default for WavOpts {
+    fn default() -> Self {
+        WavOpts {
+            sample_rate: 8000,
+            char_duration: 0.4,
+            gap_duration: 0.3,
+            pitch_jitter: 0.08,
+            tempo_jitter: 0.15,
+            noise_level: 0.05,
+            sample_bank: None
+        }
+    }
+}
+impl WavOpts {
+    setters!(
+        sample_rate: u32,
+        char_duration: f64,
+        gap_duration: f64,
+        pitch_jitter: f64,
+        tempo_jitter: f64,
+        noise_level: f64,
+        sample_bank: Option<SampleBank>
+    );
+    fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate.max(1)
+    }
+    /// 演算`check_code`的语音，并输出完整的`WAV`文件字节（含`44`字节的文件头）
+    pub fn render<R: Rng + ?Sized>(&self, check_code: &str, rng: &mut R) -> Result<Vec<u8>, WavError> {
+        encode_wav(&self.render_samples(check_code, rng)?[..], self.sample_rate_hz())
+    }
+    /// 演算`check_code`的语音，并输出`16`位`PCM`采样值
+    pub fn render_samples<R: Rng + ?Sized>(&self, check_code: &str, rng: &mut R) -> Result<Vec<i16>, WavError> {
+        // 抖动须落在`[0, 0.9]`内，否则音高或语速可能归零、甚至变负
+        for jitter in [self.pitch_jitter, self.tempo_jitter] {
+            if !(0_f64..=0.9).contains(&jitter) {
+                return Err(WavError::InvalidJitter(jitter));
+            }
+        }
+        if !(0_f64..=1_f64).contains(&self.noise_level) {
+            return Err(WavError::InvalidNoiseLevel(self.noise_level));
+        }
+        let sample_rate = self.sample_rate_hz() as f64;
+        let (pitch_jitter, tempo_jitter) = (self.pitch_jitter, self.tempo_jitter);
+        let silence = |seconds: f64| vec![0_f64; (seconds.max(0_f64) * sample_rate).round() as usize];
+        let mut track = silence(self.gap_duration);
+        for char in check_code.chars() {
+            let pitch = 1_f64 + rng.gen_range(-pitch_jitter..=pitch_jitter);
+            let tempo = 1_f64 + rng.gen_range(-tempo_jitter..=tempo_jitter);
+            match self.sample_bank.as_ref().and_then(|bank| bank.samples.get(&char).map(|samples| (bank.sample_rate, samples))) {
+                // 录音只能靠重采样变调，语速抖动仅作用于字间的停顿
+                Some((bank_rate, samples)) => track.extend(resample(&samples[..], bank_rate as f64 / sample_rate * pitch)),
+                None => {
+                    // 除了数字，其它字符都没有专属的音调。若由码点推算音调，就会出现（如`a`与`A`）听不出差别的字符。
+                    let frequencies = dtmf(char).ok_or(WavError::Unpronounceable(char))?;
+                    track.extend(tone(frequencies, self.char_duration * tempo, pitch, sample_rate));
+                }
+            }
+            track.extend(silence(self.gap_duration * tempo));
+        }
+        let noise_level = self.noise_level;
+        Ok(track.into_iter().map(|sample| {
+            let sample = sample + rng.gen_range(-noise_level..=noise_level);
+            (sample.clamp(-1_f64, 1_f64) * i16::MAX as f64).round() as i16
+        }).collect())
+    }
+}
+/// 演算语音验证码`WAV`文件失败的情形
+#[derive(Clone, Debug, PartialEq)]
+pub enum WavError {
+    /// 字符既没有录音，也不是数字
+    Unpronounceable(char),
+    InvalidJitter(f64),
+    InvalidNoiseLevel(f64),
+    /// 音频超出了`WAV`文件头所能表达的`4GB`上限
+    TooLarge
+}
+impl WavError {
+    pub fn localize(&self, language: Language) -> String {
+        match (self, language) {
+            (WavError::Unpronounceable(char), Language::Zh) => format!("样本库里没有字符“{char}”的录音，而它也不是数字"),
+            (WavError::Unpronounceable(char), Language::En) => format!("The character '{char}' has no recording in the sample bank and is not a digit"),
+            (WavError::InvalidJitter(jitter), Language::Zh) => format!("抖动必须介于 0 与 0.9 之间，实际是 {jitter}"),
+            (WavError::InvalidJitter(jitter), Language::En) => format!("The jitter must be between 0 and 0.9, got {jitter}"),
+            (WavError::InvalidNoiseLevel(noise_level), Language::Zh) => format!("底噪音量必须介于 0 与 1 之间，实际是 {noise_level}"),
+            (WavError::InvalidNoiseLevel(noise_level), Language::En) => format!("The noise level must be between 0 and 1, got {noise_level}"),
+            (WavError::TooLarge, Language::Zh) => "音频超出了 WAV 文件的大小上限".to_string(),
+            (WavError::TooLarge, Language::En) => "The audio exceeds the size limit of a WAV file".to_string()
+        }
+    }
+}
+impl Display for WavError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.localize(Language::default())[..])
+    }
+}
+// 数字的电话拨号音（行频, 列频）
+fn dtmf(char: char) -> Option<(f64, f64)> {
+    let (row, column) = match char {
+        '1' => (0, 0), '2' => (0, 1), '3' => (0, 2),
+        '4' => (1, 0), '5' => (1, 1), '6' => (1, 2),
+        '7' => (2, 0), '8' => (2, 1), '9' => (2, 2),
+        '0' => (3, 1),
+        _ => return None
+    };
+    Some(([697_f64, 770_f64, 852_f64, 941_f64][row], [1209_f64, 1336_f64, 1477_f64][column]))
+}
+fn tone((low, high): (f64, f64), seconds: f64, pitch: f64, sample_rate: f64) -> Vec<f64> {
+    let length = (seconds.max(0_f64) * sample_rate).round() as usize;
+    // 首尾各 10 毫秒的淡入淡出，以免爆音
+    let fade = (0.01 * sample_rate).max(1_f64);
+    (0..length).map(|index| {
+        let time = index as f64 / sample_rate;
+        let envelope = (index as f64 / fade).min((length - index) as f64 / fade).min(1_f64);
+        let wave = (2_f64 * PI * low * pitch * time).sin() + (2_f64 * PI * high * pitch * time).sin();
+        wave * 0.25 * envelope
+    }).collect()
+}
+// 以线性插值重采样。`step`是每个输出采样值在源采样序列上的步长。
+fn resample(samples: &[i16], step: f64) -> Vec<f64> {
+    if samples.is_empty() || step <= 0_f64 {
+        return Vec::new();
+    }
+    let length = (samples.len() as f64 / step).floor() as usize;
+    (0..length).map(|index| {
+        let position = index as f64 * step;
+        let left = position.floor() as usize;
+        let right = (left + 1).min(samples.len() - 1);
+        let weight = position - left as f64;
+        (samples[left] as f64 * (1_f64 - weight) + samples[right] as f64 * weight) / i16::MAX as f64
+    }).collect()
+}
+fn encode_wav(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>, WavError> {
+    let data_len = samples.len().checked_mul(2).and_then(|len| u32::try_from(len).ok()).ok_or(WavError::TooLarge)?;
+    let riff_len = data_len.checked_add(36).ok_or(WavError::TooLarge)?;
+    let byte_rate = sample_rate.checked_mul(2).ok_or(WavError::TooLarge)?;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&riff_len.to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16_u32.to_le_bytes());
+    // PCM、单声道
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&byte_rate.to_le_bytes());
+    bytes.extend_from_slice(&2_u16.to_le_bytes());
+    bytes.extend_from_slice(&16_u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,34 @@
+use ::wasm_yew_canvas_checkcode::{Point, verify_click_order, verify_rotation, verify_slider};
+
+#[test]
+fn click_order_within_tolerance() {
+    let targets = [Point(10_f64, 10_f64), Point(50_f64, 20_f64), Point(90_f64, 30_f64)];
+    let clicks = [Point(12_f64, 11_f64), Point(47_f64, 23_f64), Point(90_f64, 30_f64)];
+    assert!(verify_click_order(&targets, &clicks, 5_f64));
+    assert!(!verify_click_order(&targets, &clicks, 2_f64));
+}
+#[test]
+fn click_order_is_ordered() {
+    let targets = [Point(10_f64, 10_f64), Point(50_f64, 20_f64)];
+    let clicks = [Point(50_f64, 20_f64), Point(10_f64, 10_f64)];
+    assert!(!verify_click_order(&targets, &clicks, 5_f64));
+}
+#[test]
+fn click_order_needs_every_click() {
+    let targets = [Point(10_f64, 10_f64), Point(50_f64, 20_f64)];
+    assert!(!verify_click_order(&targets, &[Point(10_f64, 10_f64)], 5_f64));
+    assert!(!verify_click_order(&targets, &[Point(10_f64, 10_f64), Point(50_f64, 20_f64), Point(0_f64, 0_f64)], 5_f64));
+}
+#[test]
+fn slider_within_tolerance() {
+    assert!(verify_slider(120_f64, 123_f64, 4_f64));
+    assert!(verify_slider(120_f64, 116_f64, 4_f64));
+    assert!(!verify_slider(120_f64, 125_f64, 4_f64));
+}
+#[test]
+fn rotation_wraps_around() {
+    assert!(verify_rotation(358_f64, 3_f64, 6_f64));
+    assert!(verify_rotation(90_f64, 85_f64, 6_f64));
+    assert!(!verify_rotation(358_f64, 10_f64, 6_f64));
+    assert!(!verify_rotation(90_f64, 270_f64, 6_f64));
+}
@@ -0,0 +1,23 @@
+use ::wasm_yew_canvas_checkcode::{CheckCodeError, CssLength};
+
+#[test]
+fn parse_supported_units() {
+    assert_eq!(CssLength::parse("150px").unwrap(), CssLength::Px(150_f64));
+    assert_eq!(CssLength::parse(" 1.5e2px ").unwrap(), CssLength::Px(150_f64));
+    assert_eq!(CssLength::parse("10em").unwrap(), CssLength::Em(10_f64));
+    assert_eq!(CssLength::parse(".5REM").unwrap(), CssLength::Rem(0.5_f64));
+    assert_eq!(CssLength::parse("-50%").unwrap(), CssLength::Percent(-50_f64));
+    assert_eq!(CssLength::parse("0").unwrap(), CssLength::Px(0_f64));
+}
+#[test]
+fn reject_unsupported_values() {
+    for value in ["calc(100% - 10px)", "10vw", "12", "px", "1.px", "1e", "auto"] {
+        assert_eq!(CssLength::parse(value), Err(CheckCodeError::UnsupportedCssLength(value.to_string())), "{value}");
+    }
+}
+#[test]
+fn resolve_to_px() {
+    assert_eq!(CssLength::Em(2_f64).to_px(14_f64, 16_f64, 300_f64), 28_f64);
+    assert_eq!(CssLength::Rem(2_f64).to_px(14_f64, 16_f64, 300_f64), 32_f64);
+    assert_eq!(CssLength::Percent(50_f64).to_px(14_f64, 16_f64, 300_f64), 150_f64);
+}
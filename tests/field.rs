@@ -0,0 +1,23 @@
+mod utils;
+
+use ::deferred_future::LocalDeferredFuture;
+use ::wasm_bindgen::UnwrapThrowExt;
+use ::wasm_bindgen_test::*;
+use ::wasm_yew_canvas_checkcode::{CheckCodeField, FieldProps};
+use ::yew::Renderer;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn page_dom() {
+    let deferred_future = LocalDeferredFuture::default();
+    let defer = deferred_future.defer();
+    let div_root = utils::build_anchor_element().unwrap_throw();
+    // 仅当`UI`用户敲入了正确的图形验证码，集成测试才结束
+    Renderer::<CheckCodeField>::with_root_and_props(div_root.into(), yew::props![FieldProps {
+        on_validated: move |valid| if valid {
+            defer.borrow_mut().complete(());
+        }
+    }]).render();
+    deferred_future.await;
+}
@@ -2,12 +2,11 @@ mod utils;
 
 use ::deferred_future::LocalDeferredFuture;
 use ::gloo::dialogs;
-use ::std::{cell::RefCell, rc::Rc};
 use ::wasm_bindgen::UnwrapThrowExt;
 use ::wasm_bindgen_test::*;
-use ::wasm_yew_canvas_checkcode::{CanvasCheckCode, CheckCode, Message as CanvasCheckCodeMessage};
+use ::wasm_yew_canvas_checkcode::{CanvasCheckCode, CheckCode, CheckCodeController};
 use ::web_sys::{InputEvent, HtmlInputElement, SubmitEvent};
-use ::yew::{AttrValue, Callback, Component, Context, html, html::Scope, Html, Properties, Renderer, TargetCast};
+use ::yew::{AttrValue, Callback, Component, Context, html, Html, Properties, Renderer, TargetCast};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -27,8 +26,7 @@ struct App {
     user_name: AttrValue,
     password: AttrValue,
     check_code: AttrValue,
-    gen_check_code: String,
-    check_code_scope: Rc<RefCell<Option<Scope<CanvasCheckCode>>>>
+    check_code_controller: CheckCodeController
 }
 impl Component for App {
     type Message = Message;
@@ -43,17 +41,13 @@ impl Component for App {
             Message::ChangePassword(value) => self.password = value,
             Message::ChangeCheckCode(value) => self.check_code = value,
             Message::GenCheckCode(value) => {
-                match value {
-                    CheckCode::Initialize(value) => self.gen_check_code = value,
-                    CheckCode::Update(value) => {
-                        self.check_code = "".into();
-                        self.gen_check_code = value;
-                    }
+                if let CheckCode::Update(_) = value {
+                    self.check_code = "".into();
                 }
                 return true;
             },
             Message::SubmitForm => {
-                if self.check_code.eq(&self.gen_check_code[..]) {
+                if self.check_code_controller.verify(&self.check_code) {
                     dialogs::alert(&format!(r#"
                         图形验证码输入正确，继续提交表单
                         用户名：{}
@@ -64,16 +58,13 @@ impl Component for App {
                     dialogs::alert("图形验证码输入错误");
                     props.on_submit.emit(Err(()));
                 }
-                self.check_code_scope.borrow().as_ref().map(|scope| {
-                    scope.send_message(CanvasCheckCodeMessage::UpdateCheckCode);
-                });
+                self.check_code_controller.refresh();
             }
         }
         false
     }
     fn view(&self, ctx: &Context<Self>) -> Html {
         let scope = ctx.link();
-        let check_code_scope = Rc::clone(&self.check_code_scope);
         macro_rules! on_input {
             ($variant: ident) => {
                 scope.callback(|event: InputEvent| {
@@ -100,9 +91,7 @@ impl Component for App {
                     <span>{"验证码："}</span>
                     <input type="text" name="checkcode" placeholder="请输入验证码" value={&self.check_code} oninput={on_input!(ChangeCheckCode)} />
                 </label>
-                <label><CanvasCheckCode on_check_code_change={scope.callback(Message::GenCheckCode)} reversed_hook={move |scope| {
-                    check_code_scope.borrow_mut().replace(scope);
-                }} /></label>
+                <label><CanvasCheckCode on_check_code_change={scope.callback(Message::GenCheckCode)} controller={self.check_code_controller.clone()} /></label>
                 <label><button type="submit">{"登录"}</button></label>
             </form>
         }
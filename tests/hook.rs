@@ -0,0 +1,68 @@
+mod utils;
+
+use ::deferred_future::LocalDeferredFuture;
+use ::gloo::dialogs;
+use ::wasm_bindgen::UnwrapThrowExt;
+use ::wasm_bindgen_test::*;
+use ::wasm_yew_canvas_checkcode::{CheckCodeCanvas, use_check_code};
+use ::web_sys::{InputEvent, HtmlInputElement, SubmitEvent};
+use ::yew::{AttrValue, Callback, function_component, html, Html, Properties, Renderer, TargetCast, use_state};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    on_submit: Callback<Result<(), ()>>
+}
+#[function_component(App)]
+fn app(props: &Props) -> Html {
+    let check_code = use_state(AttrValue::default);
+    let handle = use_check_code();
+    let oninput = {
+        let check_code = check_code.clone();
+        Callback::from(move |event: InputEvent| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            check_code.set(input.value().into());
+        })
+    };
+    let onsubmit = {
+        let check_code = check_code.clone();
+        let handle = handle.clone();
+        let on_submit = props.on_submit.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            event.stop_propagation();
+            if handle.verify(&check_code) {
+                dialogs::alert("图形验证码输入正确，继续提交表单");
+                on_submit.emit(Ok(()));
+            } else {
+                dialogs::alert("图形验证码输入错误");
+                on_submit.emit(Err(()));
+            }
+            check_code.set(AttrValue::default());
+            handle.refresh();
+        })
+    };
+    html! {
+        <form {onsubmit}>
+            <label>
+                <span>{"验证码："}</span>
+                <input type="text" name="checkcode" placeholder="请输入验证码" value={&*check_code} {oninput} />
+            </label>
+            <label><CheckCodeCanvas handle={handle} /></label>
+            <label><button type="submit">{"登录"}</button></label>
+        </form>
+    }
+}
+#[wasm_bindgen_test]
+async fn page_dom() {
+    let deferred_future = LocalDeferredFuture::default();
+    let defer = deferred_future.defer();
+    let div_root = utils::build_anchor_element().unwrap_throw();
+    Renderer::<App>::with_root_and_props(div_root.into(), yew::props![Props {
+        on_submit: move |result| {
+            defer.borrow_mut().complete(result);
+        }
+    }]).render();
+    assert!(deferred_future.await.is_ok());
+}
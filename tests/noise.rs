@@ -0,0 +1,85 @@
+use ::std::{cell::RefCell, f64::consts::SQRT_2, rc::Rc};
+use ::wasm_bindgen::UnwrapThrowExt;
+use ::wasm_bindgen_test::*;
+use ::wasm_yew_canvas_checkcode::{CanvasOpts, CheckCodeError, Context2d, NoiseShape, NoiseShapeKind, Point, render, RenderTarget};
+use ::web_sys::OffscreenCanvas;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// 只记录落点、不绘制的自定义图案
+#[derive(Default)]
+struct Recorder {
+    mimics_text: bool,
+    points: RefCell<Vec<Point>>
+}
+impl NoiseShape for Recorder {
+    fn draw(&self, _: &dyn Context2d, canvas_opts: &CanvasOpts, point: &Point, _: f64, _: &str) -> Result<(), CheckCodeError> {
+        // 图案以 star_size 为外接圆半径，所以其中心总在扣除了留白的视口之内
+        let star_size = canvas_opts.star_size();
+        assert!((star_size..=canvas_opts.width() - star_size).contains(&point.0), "{point:?}");
+        assert!((star_size..=canvas_opts.height() - star_size).contains(&point.1), "{point:?}");
+        self.points.borrow_mut().push(*point);
+        Ok(())
+    }
+    fn mimics_text(&self) -> bool {
+        self.mimics_text
+    }
+}
+fn target() -> RenderTarget {
+    OffscreenCanvas::new(300, 100).unwrap_throw().into()
+}
+#[wasm_bindgen_test]
+fn custom_shapes_are_drawn() {
+    let recorder = Rc::new(Recorder::default());
+    let canvas_opts = CanvasOpts::builder().width(300_f64).height(100_f64).noise_shapes(vec![
+        (NoiseShapeKind::Custom(recorder.clone()), 1)
+    ]).build().unwrap_throw();
+    let stats = render(&target(), canvas_opts, 1_f64, "abcd").unwrap_throw();
+    assert!(stats.shape_count > 0);
+    assert_eq!(recorder.points.borrow().len(), stats.shape_count);
+}
+#[wasm_bindgen_test]
+fn text_like_shapes_avoid_glyphs() {
+    const FONT_SIZE: f64 = 22_f64;
+    let recorder = Rc::new(Recorder {mimics_text: true, ..Default::default()});
+    let canvas_opts = CanvasOpts::builder().width(300_f64).height(100_f64).star_count(200).font_size(FONT_SIZE).noise_shapes(vec![
+        (NoiseShapeKind::Custom(recorder.clone()), 1)
+    ]).build().unwrap_throw();
+    let stats = render(&target(), canvas_opts, 1_f64, "abcd").unwrap_throw();
+    assert_eq!(recorder.points.borrow().len(), stats.shape_count);
+    // 验证码字符包围盒的半边长至少是 font_size / 2 * √2，诱饵字符的是 font_size * 0.45 / 2 * √2
+    let gap = (FONT_SIZE + FONT_SIZE * 0.45) / 2_f64 * SQRT_2;
+    for point in recorder.points.borrow().iter() {
+        for center in &stats.glyph_centers {
+            assert!((point.0 - center.0).abs() >= gap || (point.1 - center.1).abs() >= gap, "{point:?} {center:?}");
+        }
+    }
+}
+#[wasm_bindgen_test]
+fn glyph_noise_is_drawn() {
+    let canvas_opts = CanvasOpts::builder().noise_shapes(vec![(NoiseShapeKind::Glyph, 1)]).build().unwrap_throw();
+    assert!(render(&target(), canvas_opts, 1_f64, "abcd").is_ok());
+}
+#[wasm_bindgen_test]
+fn dense_shapes_are_apart_and_even() {
+    const STAR_SIZE: f64 = 7_f64;
+    let recorder = Rc::new(Recorder::default());
+    let canvas_opts = CanvasOpts::builder().width(1000_f64).height(600_f64).star_size(STAR_SIZE).star_count(1000).noise_shapes(vec![
+        (NoiseShapeKind::Custom(recorder.clone()), 1)
+    ]).build().unwrap_throw();
+    let stats = render(&target(), canvas_opts, 1_f64, "abcd").unwrap_throw();
+    let points = recorder.points.borrow();
+    // 画布足够大，所以能放下全部图案，且回传的个数与实际摆放的一致
+    assert_eq!(stats.shape_count, 1000);
+    assert_eq!(points.len(), stats.shape_count);
+    for (index, point) in points.iter().enumerate() {
+        for other in &points[index + 1..] {
+            assert!((point.0 - other.0).hypot(point.1 - other.1) >= STAR_SIZE * 2_f64 - 1e-9, "{point:?} {other:?}");
+        }
+    }
+    // 均匀地铺满画布：每个象限都分得约四分之一
+    for (right, bottom) in [(false, false), (false, true), (true, false), (true, true)] {
+        let count = points.iter().filter(|point| (point.0 >= 500_f64) == right && (point.1 >= 300_f64) == bottom).count();
+        assert!(count >= 150, "{count}");
+    }
+}
@@ -0,0 +1,57 @@
+use ::std::rc::Rc;
+use ::wasm_yew_canvas_checkcode::{CanvasOpts, ChallengeMode, CheckCodeError, Context2d, Language, NoiseShape, NoiseShapeKind, OptionsError, Point};
+
+#[test]
+fn default_options_are_valid() {
+    let canvas_opts = CanvasOpts::builder().build().unwrap();
+    assert_eq!((canvas_opts.width(), canvas_opts.height(), canvas_opts.star_size(), canvas_opts.font_size()), (150_f64, 50_f64, 7_f64, 22_f64));
+    assert_eq!(canvas_opts.challenge_mode(), ChallengeMode::Text);
+    assert_eq!(canvas_opts.charset().len(), 67);
+    assert!(CanvasOpts::builder().star_count(10_000).check_code_len(300).build().is_ok());
+}
+struct Dot;
+impl NoiseShape for Dot {
+    fn draw(&self, _: &dyn Context2d, _: &CanvasOpts, _: &Point, _: f64, _: &str) -> Result<(), CheckCodeError> {
+        Ok(())
+    }
+}
+#[test]
+fn custom_noise_shapes() {
+    let dot: Rc<dyn NoiseShape> = Rc::new(Dot);
+    let kind = NoiseShapeKind::Custom(Rc::clone(&dot));
+    assert_eq!(kind, NoiseShapeKind::Custom(dot));
+    assert_ne!(kind, NoiseShapeKind::Custom(Rc::new(Dot)));
+    assert_ne!(kind, NoiseShapeKind::Star);
+    assert_eq!(NoiseShapeKind::Glyph, NoiseShapeKind::Glyph);
+    assert!(!kind.shape().mimics_text());
+    assert!(NoiseShapeKind::Glyph.shape().mimics_text());
+    assert!(CanvasOpts::builder().noise_shapes(vec![(kind, 1), (NoiseShapeKind::Glyph, 1)]).build().is_ok());
+}
+#[test]
+fn invalid_combinations() {
+    assert_eq!(CanvasOpts::builder().width(0_f64).build().unwrap_err(), OptionsError::InvalidWidth(0_f64));
+    assert_eq!(CanvasOpts::builder().height(-1_f64).build().unwrap_err(), OptionsError::InvalidHeight(-1_f64));
+    assert_eq!(CanvasOpts::builder().check_code_len(0).build().unwrap_err(), OptionsError::ZeroCheckCodeLen);
+    assert_eq!(CanvasOpts::builder().font_size(0_f64).build().unwrap_err(), OptionsError::InvalidFontSize(0_f64));
+    assert_eq!(CanvasOpts::builder().star_size(25_f64).build().unwrap_err(), OptionsError::StarSizeTooLarge {
+        star_size: 25_f64,
+        width: 150_f64,
+        height: 50_f64
+    });
+    assert!(matches!(CanvasOpts::builder().star_size(f64::NAN).build(), Err(OptionsError::InvalidStarSize(_))));
+    let click_order = || CanvasOpts::builder().challenge_mode(ChallengeMode::ClickOrder).charset(vec!['a', 'b', 'b', 'c']);
+    assert!(click_order().check_code_len(3).build().is_ok());
+    assert_eq!(click_order().check_code_len(4).build().unwrap_err(), OptionsError::CheckCodeTooLong {
+        check_code_len: 4,
+        charset_len: 3
+    });
+    assert!(CanvasOpts::builder().charset(vec!['a']).check_code_len(4).build().is_ok());
+}
+#[test]
+fn localized_errors() {
+    let error = CheckCodeError::from(OptionsError::ZeroCheckCodeLen);
+    assert_eq!(error, CheckCodeError::InvalidOptions(OptionsError::ZeroCheckCodeLen));
+    assert_eq!(error.to_string(), error.localize(Language::Zh));
+    assert_eq!(error.localize(Language::En), "The check code needs at least 1 character");
+    assert_ne!(CheckCodeError::MissingCanvas.localize(Language::Zh), CheckCodeError::MissingCanvas.localize(Language::En));
+}
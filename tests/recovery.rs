@@ -0,0 +1,47 @@
+mod utils;
+
+use ::deferred_future::LocalDeferredFuture;
+use ::wasm_bindgen::UnwrapThrowExt;
+use ::wasm_bindgen_test::*;
+use ::wasm_yew_canvas_checkcode::{CanvasCheckCode, CheckCode, CheckCodeError, OptionsError};
+use ::yew::{Callback, function_component, html, Html, Properties, Renderer, use_state};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(PartialEq, Properties)]
+pub struct Props {
+    on_recovered: Callback<String>
+}
+// 以非法的验证码长度挂载控件；一旦报错，就把长度改回合法值
+#[function_component(App)]
+fn app(props: &Props) -> Html {
+    let check_code_len = use_state(|| 0_u16);
+    let on_error = {
+        let check_code_len = check_code_len.clone();
+        Callback::from(move |error: CheckCodeError| {
+            assert_eq!(error, CheckCodeError::InvalidOptions(OptionsError::ZeroCheckCodeLen));
+            check_code_len.set(5);
+        })
+    };
+    let on_check_code_change = {
+        let on_recovered = props.on_recovered.clone();
+        Callback::from(move |check_code: CheckCode| if let CheckCode::Update(value) = check_code {
+            on_recovered.emit(value);
+        })
+    };
+    html! {
+        <CanvasCheckCode check_code_len={*check_code_len} {on_error} {on_check_code_change} />
+    }
+}
+#[wasm_bindgen_test]
+async fn recover_from_invalid_options() {
+    let deferred_future = LocalDeferredFuture::default();
+    let defer = deferred_future.defer();
+    let div_root = utils::build_anchor_element().unwrap_throw();
+    Renderer::<App>::with_root_and_props(div_root.into(), yew::props![Props {
+        on_recovered: move |check_code| {
+            defer.borrow_mut().complete(check_code);
+        }
+    }]).render();
+    assert_eq!(deferred_future.await.chars().count(), 5);
+}
@@ -18,6 +18,7 @@ fn page_dom_inner() -> Result<(), JsValue> {
             let check_code = match check_code {
                 CheckCode::Initialize(value) => value,
                 CheckCode::Update(value) => value,
+                CheckCode::Redraw(value) => value,
             };
             console::info!("从父组件收到的校验码", check_code);
         }
@@ -0,0 +1,38 @@
+mod utils;
+
+use ::deferred_future::LocalDeferredFuture;
+use ::wasm_bindgen::UnwrapThrowExt;
+use ::wasm_bindgen_test::*;
+use ::wasm_yew_canvas_checkcode::{CanvasCheckCode, CheckCode, CheckCodeError, Props as CanvasCheckCodeProps, Sizing};
+use ::yew::Renderer;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// 挂载一个`Sizing::CssOnly`模式的控件，并等待其首次绘制的结果
+async fn mount_css_only(styled: bool) -> Result<(), CheckCodeError> {
+    let deferred_future = LocalDeferredFuture::default();
+    let (on_ready, on_error) = (deferred_future.defer(), deferred_future.defer());
+    let div_root = utils::build_anchor_element().unwrap_throw();
+    // 测试样式表仅给`.root-element`内的画布设置了尺寸
+    if !styled {
+        div_root.class_list().remove_1("root-element").unwrap_throw();
+    }
+    Renderer::<CanvasCheckCode>::with_root_and_props(div_root.into(), yew::props![CanvasCheckCodeProps {
+        sizing: Sizing::CssOnly,
+        on_check_code_change: move |check_code| if let CheckCode::Initialize(_) = check_code {
+            on_ready.borrow_mut().complete(Ok(()));
+        },
+        on_error: move |error| {
+            on_error.borrow_mut().complete(Err(error));
+        }
+    }]).render();
+    deferred_future.await
+}
+#[wasm_bindgen_test]
+async fn css_only_with_stylesheet() {
+    assert_eq!(mount_css_only(true).await, Ok(()));
+}
+#[wasm_bindgen_test]
+async fn css_only_without_stylesheet() {
+    assert_eq!(mount_css_only(false).await, Err(CheckCodeError::MissingCssSize));
+}
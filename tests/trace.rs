@@ -0,0 +1,49 @@
+use ::wasm_yew_canvas_checkcode::{InteractionTrace, Point, TraceRecorder};
+
+#[test]
+fn moves_are_throttled_and_capped() {
+    let mut recorder = TraceRecorder::new(1000_f64);
+    for time in 0..100 {
+        recorder.pointer_move(Point(time as f64, 0_f64), 1000_f64 + time as f64 * 10_f64);
+    }
+    // 每 50 毫秒至多采样一次
+    let times = recorder.snapshot(2000_f64).pointer_moves.into_iter().map(|(_, time)| time).collect::<Vec<_>>();
+    assert_eq!(times, (0..20).map(|index| index as f64 * 50_f64).collect::<Vec<_>>());
+    for time in 0..1000 {
+        recorder.pointer_move(Point(0_f64, 0_f64), 2000_f64 + time as f64 * 50_f64);
+    }
+    assert_eq!(recorder.snapshot(0_f64).pointer_moves.len(), 256);
+}
+#[test]
+fn hovers_are_capped() {
+    let mut recorder = TraceRecorder::new(0_f64);
+    recorder.pointer_enter(10_f64);
+    recorder.pointer_leave(40_f64);
+    // 指针仍停留于画布之上时，其停留时长也被计入
+    recorder.pointer_enter(100_f64);
+    assert_eq!(recorder.snapshot(150_f64).hover_durations, vec![30_f64, 50_f64]);
+    for time in 0..100 {
+        recorder.pointer_enter(200_f64 + time as f64 * 10_f64);
+        recorder.pointer_leave(205_f64 + time as f64 * 10_f64);
+    }
+    recorder.pointer_enter(5000_f64);
+    assert_eq!(recorder.snapshot(6000_f64).hover_durations.len(), 32);
+}
+#[test]
+fn restart_keeps_refresh_intervals() {
+    let mut recorder = TraceRecorder::new(0_f64);
+    recorder.interact(120_f64);
+    recorder.interact(300_f64);
+    recorder.pointer_move(Point(1_f64, 2_f64), 150_f64);
+    assert_eq!(recorder.snapshot(200_f64).first_interaction, Some(120_f64));
+    recorder.restart(1000_f64);
+    recorder.restart(1500_f64);
+    assert_eq!(recorder.snapshot(1500_f64), InteractionTrace {
+        refresh_intervals: vec![1000_f64, 500_f64],
+        ..Default::default()
+    });
+    for time in 0..100 {
+        recorder.restart(2000_f64 + time as f64);
+    }
+    assert_eq!(recorder.snapshot(0_f64).refresh_intervals.len(), 32);
+}
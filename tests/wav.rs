@@ -0,0 +1,65 @@
+use ::rand::rngs::{mock::StepRng, OsRng};
+use ::wasm_yew_canvas_checkcode::{SampleBank, WavError, WavOpts};
+
+fn steady() -> WavOpts {
+    WavOpts::default().pitch_jitter(0.0).tempo_jitter(0.0).noise_level(0.0)
+}
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+#[test]
+fn wav_header() {
+    let bank = SampleBank::new(16000).insert('a', vec![1000; 100]).insert('b', vec![-1000; 100]);
+    let bytes = WavOpts::default().sample_rate(16000).sample_bank(Some(bank)).render("a1b2", &mut OsRng).unwrap();
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+    assert_eq!(&bytes[8..16], b"WAVEfmt ");
+    assert_eq!(u16::from_le_bytes([bytes[22], bytes[23]]), 1);
+    assert_eq!(u32_at(&bytes, 24), 16000);
+    assert_eq!(&bytes[36..40], b"data");
+    assert_eq!(u32_at(&bytes, 40) as usize, bytes.len() - 44);
+}
+#[test]
+fn steady_length() {
+    let samples = steady().sample_rate(8000).char_duration(0.5).gap_duration(0.25).render_samples("12", &mut StepRng::new(0, 1)).unwrap();
+    // 首部停顿 + 2 x（字符 + 停顿）
+    assert_eq!(samples.len(), 2000 + 2 * (4000 + 2000));
+    assert!(samples[..2000].iter().all(|&sample| sample == 0));
+    assert!(samples[2000..6000].iter().any(|&sample| sample != 0));
+}
+#[test]
+fn sample_bank_is_spliced() {
+    let bank = SampleBank::new(8000).insert('7', vec![1000; 100]);
+    let samples = steady().sample_rate(8000).gap_duration(0.0).sample_bank(Some(bank)).render_samples("7", &mut StepRng::new(0, 1)).unwrap();
+    assert_eq!(samples, vec![1000; 100]);
+}
+#[test]
+fn sample_bank_is_resampled() {
+    let bank = SampleBank::new(16000).insert('7', vec![1000; 200]);
+    let samples = steady().sample_rate(8000).gap_duration(0.0).sample_bank(Some(bank)).render_samples("7", &mut StepRng::new(0, 1)).unwrap();
+    assert_eq!(samples.len(), 100);
+}
+#[test]
+fn noise_is_bounded() {
+    let samples = steady().noise_level(0.1).gap_duration(1.0).render_samples("", &mut OsRng).unwrap();
+    let bound = (0.1 * i16::MAX as f64).ceil() as i16;
+    assert!(samples.iter().all(|sample| sample.abs() <= bound));
+    assert!(samples.iter().any(|&sample| sample != 0));
+}
+#[test]
+fn tempo_jitter_is_bounded() {
+    let opts = steady().sample_rate(8000).char_duration(1.0).gap_duration(0.0).tempo_jitter(0.2);
+    for _ in 0..20 {
+        let len = opts.render_samples("5", &mut OsRng).unwrap().len();
+        assert!((6400..=9600).contains(&len), "{len}");
+    }
+}
+#[test]
+fn invalid_opts() {
+    assert_eq!(steady().render("a1", &mut OsRng).unwrap_err(), WavError::Unpronounceable('a'));
+    assert_eq!(steady().pitch_jitter(-0.1).render("1", &mut OsRng).unwrap_err(), WavError::InvalidJitter(-0.1));
+    assert!(matches!(steady().tempo_jitter(f64::NAN).render("1", &mut OsRng), Err(WavError::InvalidJitter(_))));
+    assert!(matches!(steady().noise_level(f64::NAN).render("1", &mut OsRng), Err(WavError::InvalidNoiseLevel(_))));
+    assert_eq!(steady().noise_level(-0.1).render("1", &mut OsRng).unwrap_err(), WavError::InvalidNoiseLevel(-0.1));
+    assert_eq!(steady().sample_rate(u32::MAX).gap_duration(0.0).render("", &mut OsRng).unwrap_err(), WavError::TooLarge);
+}